@@ -0,0 +1,87 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+pub use self::flash_borrower::{SampleFlashBorrower, SampleFlashBorrowerRef};
+
+/// Sample flash loan borrower of the fee token.
+/// It only accepts loans it requested itself from the trusted lender and pays the fee from its own balance
+#[openbrush::contract]
+pub mod flash_borrower {
+    use ink::codegen::Env;
+    use ink::env::CallFlags;
+    use ink::prelude::vec::Vec;
+    use logics_pkg::traits::{admin::Psp22Ref, flash_lender::*};
+    use openbrush::contracts::ownable::*;
+    use openbrush::contracts::traits::psp22::PSP22Error;
+    use openbrush::modifiers;
+    use openbrush::traits::{Storage, String};
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct SampleFlashBorrower {
+        #[storage_field]
+        ownable: ownable::Data,
+        lender: AccountId,
+    }
+
+    impl Ownable for SampleFlashBorrower {}
+
+    impl SampleFlashBorrower {
+        #[ink(constructor)]
+        pub fn new(lender: AccountId) -> Self {
+            let mut instance = Self::default();
+
+            instance._init_with_owner(instance.env().caller());
+            instance.lender = lender;
+            instance
+        }
+
+        /// Borrow `amount` of the lender token for the duration of the call. Only the owner can borrow,
+        /// the fee is paid from the balance of this contract
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn flash_borrow(&mut self, amount: Balance, data: Vec<u8>) -> Result<(), PSP22Error> {
+            let receiver = self.env().account_id();
+
+            // The lender calls `on_flash_loan` of this contract during the loan
+            let result = FlashLenderRef::flash_loan_builder(&self.lender, receiver, self.lender, amount, data)
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .try_invoke();
+
+            match result {
+                Ok(Ok(result)) => result,
+                _ => Err(PSP22Error::Custom(String::from("FB::FlashLoanFailed"))),
+            }
+        }
+
+        #[ink(message)]
+        pub fn get_lender(&self) -> AccountId {
+            self.lender
+        }
+    }
+
+    impl FlashBorrower for SampleFlashBorrower {
+        #[ink(message)]
+        fn on_flash_loan(
+            &mut self,
+            initiator: AccountId,
+            token: AccountId,
+            amount: Balance,
+            fee: Balance,
+            _data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            if self.env().caller() != self.lender || token != self.lender {
+                return Err(PSP22Error::Custom(String::from("FB::UntrustedLender")));
+            }
+            if initiator != self.env().account_id() {
+                return Err(PSP22Error::Custom(String::from("FB::UntrustedInitiator")));
+            }
+
+            // Arbitrage or liquidation logic goes here
+
+            let repayment = amount
+                .checked_add(fee)
+                .ok_or_else(|| PSP22Error::Custom(String::from("FB::Overflow")))?;
+            Psp22Ref::approve(&token, self.lender, repayment)
+        }
+    }
+}
@@ -0,0 +1,385 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// Holds PSP22 tokens and lets the recipients of an off-chain list claim their share with a Merkle proof.
+/// Leaves are the Blake2x256 hash of the SCALE encoded `(index, account, amount)`, pairs of nodes are hashed
+/// in sorted order. The airdrop contract has to be fee exempt in a fee token, otherwise claims are taxed
+#[openbrush::contract]
+pub mod merkle_airdrop {
+    use ink::codegen::{EmitEvent, Env};
+    use ink::env::hash::{Blake2x256, HashOutput};
+    use ink::env::CallFlags;
+    use ink::prelude::vec::Vec;
+    use logics_pkg::impls::psp34_artzero_support::admin::*;
+    use logics_pkg::traits::{admin::*, error::Error, roles::*};
+    use openbrush::{
+        contracts::access_control::*, contracts::ownable::*, modifiers, storage::Mapping, traits::Storage,
+    };
+    use scale::Encode;
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct MerkleAirdrop {
+        #[storage_field]
+        ownable: ownable::Data,
+        #[storage_field]
+        access_control: access_control::Data,
+        #[storage_field]
+        admin_data: admin::data::Data,
+        token: AccountId,
+        merkle_root: [u8; 32],
+        expiry: Timestamp,
+        /// Claimed indices, one bit per index and 128 indices per word
+        claimed: Mapping<u32, u128>,
+        claimed_count: u32,
+    }
+
+    /// Event emitted when a recipient claims its share.
+    #[ink(event)]
+    pub struct Claimed {
+        index: u32,
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    impl Ownable for MerkleAirdrop {}
+    impl AccessControl for MerkleAirdrop {}
+
+    impl AdminTrait for MerkleAirdrop {
+        /// Same as the default recovery, but the airdropped token can only be swept once the airdrop expired
+        #[ink(message)]
+        #[modifiers(only_role(TREASURER))]
+        fn tranfer_psp22(
+            &mut self,
+            psp22_contract_address: AccountId,
+            amount: Balance,
+            receiver: AccountId,
+        ) -> Result<(), Error> {
+            if psp22_contract_address == self.token && !self._is_expired() {
+                return Err(Error::AirdropNotExpired);
+            }
+
+            let result = Psp22Ref::transfer_builder(&psp22_contract_address, receiver, amount, Vec::<u8>::new())
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .try_invoke();
+            if !matches!(result, Ok(Ok(Ok(_)))) {
+                return Err(Error::WithdrawPSP22Error);
+            }
+            Ok(())
+        }
+    }
+
+    impl MerkleAirdrop {
+        #[ink(constructor)]
+        pub fn new(token: AccountId, merkle_root: [u8; 32], expiry: Timestamp) -> Self {
+            let mut instance = Self::default();
+
+            instance._init_with_owner(instance.env().caller());
+            instance._init_with_admin(instance.env().caller());
+            instance._setup_role(TREASURER, instance.env().caller());
+            instance.token = token;
+            instance.merkle_root = merkle_root;
+            instance.expiry = expiry;
+            instance
+        }
+
+        /// Transfer `amount` of the token to `account` if the leaf `(index, account, amount)` is part of the tree
+        #[ink(message)]
+        pub fn claim(
+            &mut self,
+            index: u32,
+            account: AccountId,
+            amount: Balance,
+            proof: Vec<[u8; 32]>,
+        ) -> Result<(), Error> {
+            if self._is_expired() {
+                return Err(Error::AirdropExpired);
+            }
+            if self.is_claimed(index) {
+                return Err(Error::AlreadyClaimed);
+            }
+
+            let leaf = hash(&(index, account, amount).encode());
+            if !self._verify(&proof, leaf) {
+                return Err(Error::InvalidProof);
+            }
+
+            self._set_claimed(index);
+            self.claimed_count = self.claimed_count.saturating_add(1);
+            // A failed or reverted transfer fails the whole claim, so the index is not burned
+            let result = Psp22Ref::transfer_builder(&self.token, account, amount, Vec::<u8>::new())
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .try_invoke();
+            if !matches!(result, Ok(Ok(Ok(_)))) {
+                return Err(Error::CannotTransfer);
+            }
+            self.env().emit_event(Claimed { index, account, amount });
+
+            Ok(())
+        }
+
+        /// Replace the Merkle root to fix the list. Fails once the first share was claimed,
+        /// the claimed indices would carry over to the new list
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn set_merkle_root(&mut self, merkle_root: [u8; 32]) -> Result<(), Error> {
+            if self.claimed_count > 0 {
+                return Err(Error::ClaimsStarted);
+            }
+            self.merkle_root = merkle_root;
+            Ok(())
+        }
+
+        /// Number of shares claimed so far
+        #[ink(message)]
+        pub fn get_claimed_count(&self) -> u32 {
+            self.claimed_count
+        }
+
+        #[ink(message)]
+        pub fn is_claimed(&self, index: u32) -> bool {
+            let word = self.claimed.get(&(index / 128)).unwrap_or(0);
+            word & (1 << (index % 128)) != 0
+        }
+
+        #[ink(message)]
+        pub fn get_merkle_root(&self) -> [u8; 32] {
+            self.merkle_root
+        }
+
+        #[ink(message)]
+        pub fn get_token(&self) -> AccountId {
+            self.token
+        }
+
+        /// Timestamp after which claims are closed and the owner can sweep the leftovers
+        #[ink(message)]
+        pub fn get_expiry(&self) -> Timestamp {
+            self.expiry
+        }
+
+        fn _is_expired(&self) -> bool {
+            self.env().block_timestamp() > self.expiry
+        }
+
+        fn _set_claimed(&mut self, index: u32) {
+            let word = self.claimed.get(&(index / 128)).unwrap_or(0);
+            self.claimed.insert(&(index / 128), &(word | (1 << (index % 128))));
+        }
+
+        fn _verify(&self, proof: &[[u8; 32]], leaf: [u8; 32]) -> bool {
+            let root = proof.iter().fold(leaf, |node, sibling| hash_pair(node, *sibling));
+
+            root == self.merkle_root
+        }
+    }
+
+    /// Parent of two nodes, they are hashed in sorted order so proofs do not need the position of each sibling
+    fn hash_pair(first: [u8; 32], second: [u8; 32]) -> [u8; 32] {
+        let (left, right) = if first <= second {
+            (first, second)
+        } else {
+            (second, first)
+        };
+        let mut pair = [0u8; 64];
+        pair[..32].copy_from_slice(&left);
+        pair[32..].copy_from_slice(&right);
+        hash(&pair)
+    }
+
+    fn hash(input: &[u8]) -> [u8; 32] {
+        let mut output = <Blake2x256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Blake2x256>(input, &mut output);
+        output
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        const EXPIRY: Timestamp = 1_000_000;
+        const TOKEN: [u8; 32] = [7; 32];
+
+        fn leaf(index: u32, account: AccountId, amount: Balance) -> [u8; 32] {
+            hash(&(index, account, amount).encode())
+        }
+
+        /// Alice deploys an airdrop of 100, 200, 300 and 400 tokens to Bob, Charlie, Eve and Frank.
+        /// Returns the leaves in index order, the contract lives at Django's address
+        fn setup() -> (MerkleAirdrop, Vec<[u8; 32]>, test::DefaultAccounts<DefaultEnvironment>) {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+
+            let leaves = Vec::from([
+                leaf(0, accounts.bob, 100),
+                leaf(1, accounts.charlie, 200),
+                leaf(2, accounts.eve, 300),
+                leaf(3, accounts.frank, 400),
+            ]);
+            let root = hash_pair(hash_pair(leaves[0], leaves[1]), hash_pair(leaves[2], leaves[3]));
+
+            (MerkleAirdrop::new(TOKEN.into(), root, EXPIRY), leaves, accounts)
+        }
+
+        #[ink::test]
+        fn valid_proofs_are_accepted() {
+            let (airdrop, leaves, _) = setup();
+
+            assert!(airdrop._verify(&[leaves[1], hash_pair(leaves[2], leaves[3])], leaves[0]));
+            assert!(airdrop._verify(&[leaves[3], hash_pair(leaves[0], leaves[1])], leaves[2]));
+        }
+
+        #[ink::test]
+        fn invalid_proofs_are_rejected() {
+            let (mut airdrop, leaves, accounts) = setup();
+            let proof = Vec::from([leaves[1], hash_pair(leaves[2], leaves[3])]);
+
+            assert!(!airdrop._verify(&proof[..1], leaves[0]));
+            assert!(!airdrop._verify(&[leaves[2], hash_pair(leaves[2], leaves[3])], leaves[0]));
+            assert_eq!(
+                airdrop.claim(0, accounts.bob, 101, proof.clone()),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(
+                airdrop.claim(0, accounts.charlie, 100, proof.clone()),
+                Err(Error::InvalidProof)
+            );
+            assert_eq!(airdrop.claim(1, accounts.bob, 100, proof), Err(Error::InvalidProof));
+            assert!(!airdrop.is_claimed(0));
+            assert!(!airdrop.is_claimed(1));
+        }
+
+        #[ink::test]
+        fn claimed_indices_can_not_be_claimed_again() {
+            let (mut airdrop, leaves, accounts) = setup();
+
+            airdrop._set_claimed(0);
+            airdrop._set_claimed(128);
+            assert!(airdrop.is_claimed(0));
+            assert!(!airdrop.is_claimed(1));
+            assert!(!airdrop.is_claimed(127));
+            assert!(airdrop.is_claimed(128));
+
+            let proof = Vec::from([leaves[1], hash_pair(leaves[2], leaves[3])]);
+            assert_eq!(airdrop.claim(0, accounts.bob, 100, proof), Err(Error::AlreadyClaimed));
+        }
+
+        #[ink::test]
+        fn claims_close_after_the_expiry() {
+            let (mut airdrop, leaves, accounts) = setup();
+            let proof = Vec::from([leaves[1], hash_pair(leaves[2], leaves[3])]);
+
+            test::set_block_timestamp::<DefaultEnvironment>(EXPIRY + 1);
+            assert_eq!(airdrop.claim(0, accounts.bob, 100, proof), Err(Error::AirdropExpired));
+        }
+
+        #[ink::test]
+        fn airdrop_token_is_not_swept_before_the_expiry() {
+            let (mut airdrop, _, accounts) = setup();
+
+            test::set_block_timestamp::<DefaultEnvironment>(EXPIRY);
+            assert_eq!(
+                airdrop.tranfer_psp22(TOKEN.into(), 1_000, accounts.alice),
+                Err(Error::AirdropNotExpired)
+            );
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                airdrop.tranfer_psp22(TOKEN.into(), 1_000, accounts.bob),
+                Err(Error::AccessControlError(AccessControlError::MissingRole))
+            );
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use ink_e2e::{build_message, AccountKeyring};
+        use logics_pkg::traits::psp22_fee::Psp22Fee;
+        use my_contracts::my_psp22::ContractRef as TokenRef;
+        use openbrush::contracts::traits::psp22::PSP22;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Both airdrops hold 10_000 tokens and Bob's share of 1_000 is the only leaf, so the proof is empty.
+        /// The first one never expires, the second one expired at deployment
+        #[ink_e2e::test(additional_contracts = "../psp22/Cargo.toml")]
+        async fn claim_once_and_sweep_after_the_expiry(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(AccountKeyring::Bob);
+            let root = hash(&(0u32, bob, 1_000 as Balance).encode());
+
+            let constructor = TokenRef::new(1_000_000, 2_000_000, None, None, 18, 10_000, 10_000, 0);
+            let token = client
+                .instantiate("my_contracts", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("token instantiate failed")
+                .account_id;
+            let enable_trading = build_message::<TokenRef>(token.clone()).call(|token| token.enable_trading());
+            client
+                .call(&ink_e2e::alice(), enable_trading, 0, None)
+                .await
+                .expect("enable_trading failed");
+
+            let mut airdrops = Vec::new();
+            for expiry in [Timestamp::MAX, 0] {
+                let constructor = MerkleAirdropRef::new(token, root, expiry);
+                let airdrop = client
+                    .instantiate("merkle_airdrop", &ink_e2e::alice(), constructor, 0, None)
+                    .await
+                    .expect("airdrop instantiate failed")
+                    .account_id;
+                let fund =
+                    build_message::<TokenRef>(token.clone()).call(|token| token.transfer(airdrop, 10_000, Vec::new()));
+                client
+                    .call(&ink_e2e::alice(), fund, 0, None)
+                    .await
+                    .expect("fund failed");
+                airdrops.push(airdrop);
+            }
+            let (open, expired) = (airdrops[0], airdrops[1]);
+
+            let claim = build_message::<MerkleAirdropRef>(open.clone())
+                .call(|airdrop| airdrop.claim(0, bob, 1_000, Vec::new()));
+            client
+                .call(&ink_e2e::alice(), claim, 0, None)
+                .await
+                .expect("claim failed");
+            let balance = build_message::<TokenRef>(token.clone()).call(|token| token.balance_of(bob));
+            let result = client.call_dry_run(&ink_e2e::alice(), &balance, 0, None).await;
+            assert_eq!(result.return_value(), 1_000);
+
+            let claim = build_message::<MerkleAirdropRef>(open.clone())
+                .call(|airdrop| airdrop.claim(0, bob, 1_000, Vec::new()));
+            let result = client.call_dry_run(&ink_e2e::alice(), &claim, 0, None).await;
+            assert_eq!(result.return_value(), Err(Error::AlreadyClaimed));
+            let set_root =
+                build_message::<MerkleAirdropRef>(open.clone()).call(|airdrop| airdrop.set_merkle_root([0; 32]));
+            let result = client.call_dry_run(&ink_e2e::alice(), &set_root, 0, None).await;
+            assert_eq!(result.return_value(), Err(Error::ClaimsStarted));
+            let sweep = build_message::<MerkleAirdropRef>(open.clone())
+                .call(|airdrop| airdrop.tranfer_psp22(token, 9_000, alice));
+            let result = client.call_dry_run(&ink_e2e::alice(), &sweep, 0, None).await;
+            assert_eq!(result.return_value(), Err(Error::AirdropNotExpired));
+
+            let claim = build_message::<MerkleAirdropRef>(expired.clone())
+                .call(|airdrop| airdrop.claim(0, bob, 1_000, Vec::new()));
+            let result = client.call_dry_run(&ink_e2e::alice(), &claim, 0, None).await;
+            assert_eq!(result.return_value(), Err(Error::AirdropExpired));
+            let sweep = build_message::<MerkleAirdropRef>(expired.clone())
+                .call(|airdrop| airdrop.tranfer_psp22(token, 10_000, alice));
+            client
+                .call(&ink_e2e::alice(), sweep, 0, None)
+                .await
+                .expect("sweep failed");
+            let balance = build_message::<TokenRef>(token.clone()).call(|token| token.balance_of(expired));
+            let result = client.call_dry_run(&ink_e2e::alice(), &balance, 0, None).await;
+            assert_eq!(result.return_value(), 0);
+
+            Ok(())
+        }
+    }
+}
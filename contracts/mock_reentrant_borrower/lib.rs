@@ -0,0 +1,63 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+pub use self::mock_reentrant_borrower::{MockReentrantBorrower, MockReentrantBorrowerRef};
+
+/// Borrower stand-in used to exercise the reentrancy guard of the flash lender.
+/// It requests a second loan from inside `on_flash_loan`, records the result and repays the first loan
+#[openbrush::contract]
+pub mod mock_reentrant_borrower {
+    use ink::codegen::Env;
+    use ink::env::CallFlags;
+    use ink::prelude::vec::Vec;
+    use logics_pkg::traits::{admin::Psp22Ref, flash_lender::*};
+    use openbrush::contracts::traits::psp22::PSP22Error;
+    use openbrush::traits::String;
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct MockReentrantBorrower {
+        nested_error: Option<String>,
+    }
+
+    impl MockReentrantBorrower {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Error of the loan requested from inside the last `on_flash_loan`, `None` if it was granted
+        #[ink(message)]
+        pub fn nested_error(&self) -> Option<String> {
+            self.nested_error.clone()
+        }
+    }
+
+    impl FlashBorrower for MockReentrantBorrower {
+        #[ink(message)]
+        fn on_flash_loan(
+            &mut self,
+            _initiator: AccountId,
+            token: AccountId,
+            amount: Balance,
+            fee: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let lender = self.env().caller();
+            let receiver = self.env().account_id();
+
+            let result = FlashLenderRef::flash_loan_builder(&lender, receiver, token, amount, data)
+                .call_flags(CallFlags::default().set_allow_reentry(true))
+                .try_invoke();
+            self.nested_error = match result {
+                Ok(Ok(Ok(()))) => None,
+                Ok(Ok(Err(PSP22Error::Custom(message)))) => Some(message),
+                _ => Some(String::from("MB::CallFailed")),
+            };
+
+            let repayment = amount
+                .checked_add(fee)
+                .ok_or_else(|| PSP22Error::Custom(String::from("MB::Overflow")))?;
+            Psp22Ref::approve(&token, lender, repayment)
+        }
+    }
+}
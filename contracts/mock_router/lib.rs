@@ -0,0 +1,60 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+pub use self::mock_router::{MockRouter, MockRouterRef};
+
+/// Router stand-in used to exercise swap and liquify of the fee token.
+/// It pulls the tokens from the caller and books them as liquidity of the receiver, no swap happens
+#[openbrush::contract]
+pub mod mock_router {
+    use ink::codegen::Env;
+    use ink::prelude::vec::Vec;
+    use logics_pkg::traits::{admin::Psp22Ref, router::*};
+    use openbrush::contracts::traits::psp22::PSP22Error;
+    use openbrush::storage::Mapping;
+
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct MockRouter {
+        liquidity: Mapping<AccountId, Balance>,
+        total_liquidity: Balance,
+    }
+
+    impl MockRouter {
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Liquidity booked for `account` as LP receiver
+        #[ink(message)]
+        pub fn liquidity_of(&self, account: AccountId) -> Balance {
+            self.liquidity.get(&account).unwrap_or(0)
+        }
+
+        #[ink(message)]
+        pub fn total_liquidity(&self) -> Balance {
+            self.total_liquidity
+        }
+    }
+
+    impl LiquidityRouter for MockRouter {
+        #[ink(message)]
+        fn swap_and_add_liquidity(
+            &mut self,
+            token: AccountId,
+            amount: Balance,
+            lp_receiver: AccountId,
+        ) -> Result<Balance, PSP22Error> {
+            let caller = self.env().caller();
+            let router = self.env().account_id();
+
+            Psp22Ref::transfer_from(&token, caller, router, amount, Vec::<u8>::new())?;
+
+            let liquidity = self.liquidity_of(lp_receiver) + amount;
+            self.liquidity.insert(&lp_receiver, &liquidity);
+            self.total_liquidity += amount;
+
+            Ok(amount)
+        }
+    }
+}
@@ -4,16 +4,37 @@
 #[openbrush::contract]
 pub mod my_psp22 {
     // imports from openbrush
-    use ink::codegen::Env;
+    use ink::codegen::{EmitEvent, Env};
     use ink::prelude::vec::Vec;
+    use logics_pkg::impls::dividend::dividend::DividendInternal;
+    use logics_pkg::impls::dividend::*;
+    use logics_pkg::impls::flash_lender::flash_lender::FlashLenderInternal;
+    use logics_pkg::impls::flash_lender::*;
+    use logics_pkg::impls::psp22_fee::psp22_fee::Psp22FeeInternal;
+    use logics_pkg::impls::psp22_permit::*;
+    use logics_pkg::impls::psp22_votes::psp22_votes::Psp22VotesInternal;
+    use logics_pkg::impls::psp22_votes::*;
+    use logics_pkg::traits::dividend::*;
+    use logics_pkg::traits::error::Psp22FeeError;
+    use logics_pkg::traits::flash_lender::*;
+    use logics_pkg::traits::pause_manager::*;
     use logics_pkg::traits::psp22_fee::*;
+    use logics_pkg::traits::psp22_permit::*;
+    use logics_pkg::traits::psp22_votes::*;
+    use logics_pkg::traits::roles::*;
     use logics_pkg::{impls::psp22_fee::*, traits::psp22_fee::*};
+    use openbrush::contracts::access_control::*;
     use openbrush::contracts::ownable::*;
-    use openbrush::contracts::psp22::extensions::metadata::*;
+    use openbrush::contracts::pausable::*;
+    use openbrush::contracts::psp22::extensions::{burnable::*, metadata::*, mintable::*};
+    use openbrush::modifiers;
     use openbrush::traits::Storage;
     use openbrush::traits::String;
     use openbrush::traits::ZERO_ADDRESS;
 
+    /// Maximum number of transfers in one `batch_transfer` or `airdrop` call when the contract is deployed
+    pub const DEFAULT_MAX_BATCH_LENGTH: u32 = 100;
+
     #[ink(storage)]
     #[derive(Default, Storage)]
     pub struct Contract {
@@ -22,20 +43,171 @@ pub mod my_psp22 {
         #[storage_field]
         ownable: ownable::Data,
         #[storage_field]
+        access_control: access_control::Data,
+        #[storage_field]
         metadata: metadata::Data,
         #[storage_field]
         psp22_fee: psp22_fee::Data,
+        #[storage_field]
+        pausable: pausable::Data,
+        #[storage_field]
+        votes: psp22_votes::Data,
+        #[storage_field]
+        permit: psp22_permit::Data,
+        #[storage_field]
+        dividend: dividend::Data,
+        #[storage_field]
+        flash_lender: flash_lender::Data,
+        cap: Balance,
+        max_batch_length: u32,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    /// Event emitted when an approval occurs that `spender` is allowed to withdraw
+    /// up to the amount of `value` tokens from `owner`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when a transfer from `from` to `to` is taxed.
+    #[ink(event)]
+    pub struct FeeCharged {
+        #[ink(topic)]
+        from: AccountId,
+        #[ink(topic)]
+        to: AccountId,
+        fee: Balance,
+    }
+
+    /// Event emitted when one of the fees is changed, carries all current fees in basis points.
+    #[ink(event)]
+    pub struct FeeUpdated {
+        fee: u128,
+        buy_fee: u128,
+        sell_fee: u128,
+    }
+
+    /// Event emitted when the max transaction policy is changed.
+    #[ink(event)]
+    pub struct MaxTxUpdated {
+        max_tx: Limit,
+    }
+
+    /// Event emitted when the max wallet policy is changed.
+    #[ink(event)]
+    pub struct MaxWalletUpdated {
+        max_wallet: Limit,
+    }
+
+    /// Event emitted when an account is added to or removed from the blacklist.
+    #[ink(event)]
+    pub struct BlacklistUpdated {
+        #[ink(topic)]
+        account: AccountId,
+        blacklisted: bool,
+    }
+
+    /// Event emitted once per `batch_transfer` or `airdrop` call with the totals of the batch.
+    #[ink(event)]
+    pub struct BatchTransfer {
+        #[ink(topic)]
+        from: AccountId,
+        recipients: u32,
+        total: Balance,
+        fee_exempt: bool,
+    }
+
+    /// Event emitted once when the owner opens trading.
+    #[ink(event)]
+    pub struct TradingEnabled {
+        block: BlockNumber,
+    }
+
+    /// Event emitted when an account changes its delegate.
+    #[ink(event)]
+    pub struct DelegateChanged {
+        #[ink(topic)]
+        delegator: AccountId,
+        #[ink(topic)]
+        from_delegate: AccountId,
+        #[ink(topic)]
+        to_delegate: AccountId,
+    }
+
+    /// Event emitted when the voting power of a delegate changes.
+    #[ink(event)]
+    pub struct DelegateVotesChanged {
+        #[ink(topic)]
+        delegate: AccountId,
+        previous_votes: Balance,
+        new_votes: Balance,
+    }
+
+    /// Event emitted when native currency or reward tokens are shared between the holders.
+    #[ink(event)]
+    pub struct DividendsDistributed {
+        #[ink(topic)]
+        from: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a holder withdraws its dividends.
+    #[ink(event)]
+    pub struct DividendWithdrawn {
+        #[ink(topic)]
+        to: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a flash loan is repaid.
+    #[ink(event)]
+    pub struct FlashLoan {
+        #[ink(topic)]
+        receiver: AccountId,
+        amount: Balance,
+        fee: Balance,
+    }
+
+    /// Event emitted when the contract is paused.
+    #[ink(event)]
+    pub struct Paused {
+        account: AccountId,
+    }
+
+    /// Event emitted when the contract is unpaused.
+    #[ink(event)]
+    pub struct Unpaused {
+        account: AccountId,
     }
 
     // Section contains default implementation without any modifications
 
     impl Ownable for Contract {}
     impl PSP22Metadata for Contract {}
+    impl AccessControl for Contract {}
+    impl Pausable for Contract {}
+    impl PauseManager for Contract {}
 
     impl Contract {
         #[ink(constructor)]
+        #[allow(clippy::too_many_arguments)]
         pub fn new(
             initial_supply: Balance,
+            cap: Balance,
             name: Option<String>,
             symbol: Option<String>,
             decimal: u8,
@@ -43,43 +215,1149 @@ pub mod my_psp22 {
             max_tx: u128,
             fee: u128,
         ) -> Self {
+            assert!(initial_supply <= cap, "Initial supply exceeds cap");
             let mut _instance = Self::default();
+            _instance.cap = cap;
+            _instance.max_batch_length = DEFAULT_MAX_BATCH_LENGTH;
             _instance
                 ._mint_to(_instance.env().caller(), initial_supply)
                 .expect("Should mint");
             _instance._init_with_owner(_instance.env().caller());
+            _instance._init_with_admin(_instance.env().caller());
+            _instance._setup_role(FEE_MANAGER, _instance.env().caller());
+            _instance._setup_role(MINTER, _instance.env().caller());
+            _instance._setup_role(PAUSER, _instance.env().caller());
             _instance.metadata.name = name;
             _instance.metadata.symbol = symbol;
             _instance.metadata.decimals = decimal;
-            _instance.psp22_fee.max_wallet = initial_supply * max_wallet / 100;
-            _instance.psp22_fee.max_tx = initial_supply * max_tx / 100;
-            _instance.psp22_fee.fee = fee;
+            _instance
+                ._set_max_wallet(Limit::Percentage(max_wallet))
+                .expect("Invalid max wallet");
+            _instance
+                ._set_max_tx(Limit::Percentage(max_tx))
+                .expect("Invalid max tx");
+            _instance.psp22_fee.buy_fee = fee;
+            _instance.psp22_fee.sell_fee = fee;
+            _instance._set_fee(fee).expect("Fee too high");
+            let treasury = FeeRecipient::Account(_instance.env().caller());
+            _instance
+                ._set_fee_recipients(Vec::from([(treasury, psp22_fee::FEE_DENOMINATOR)]))
+                .expect("Should set fee recipients");
+            let contract = _instance.env().account_id();
+            for account in [_instance.env().caller(), contract] {
+                _instance.psp22_fee.fee_exempt.insert(&account, &true);
+                _instance.psp22_fee.limit_exempt.insert(&account, &true);
+            }
+            // The treasury collects the fees and the contract holds the liquidity fee, neither earns dividends
+            for account in [_instance.env().caller(), contract] {
+                _instance
+                    ._exclude_from_dividends(account)
+                    .expect("Should exclude from dividends");
+            }
             _instance
         }
+
+        /// Maximum total supply, minting past it fails with `F::CapExceeded`
+        #[ink(message)]
+        pub fn cap(&self) -> Balance {
+            self.cap
+        }
+
+        /// Send tokens from the caller to every recipient of `transfers`. Each transfer pays the usual fee
+        /// and respects the limits, the whole batch counts as a single send for the cooldown.
+        /// Either all transfers succeed or the whole call is reverted
+        #[ink(message)]
+        pub fn batch_transfer(&mut self, transfers: Vec<(AccountId, Balance)>) -> Result<(), PSP22Error> {
+            let from = self.env().caller();
+            let total = self._check_batch(&transfers)?;
+            if total > self._balance_of(&from) {
+                return Err(PSP22Error::InsufficientBalance);
+            }
+
+            // Validate every entry before the first token moves
+            for (to, value) in transfers.iter() {
+                self._check_transfer(&from, to, *value)?;
+            }
+            self._apply_cooldown(&from, &transfers[0].0)?;
+
+            for (to, value) in transfers.iter() {
+                self._charge_fee_and_transfer(from, *to, *value, Vec::<u8>::new())?;
+            }
+            self.env().emit_event(BatchTransfer {
+                from,
+                recipients: transfers.len() as u32,
+                total,
+                fee_exempt: false,
+            });
+
+            Ok(())
+        }
+
+        /// `batch_transfer` for accounts with the MINTER role which skips the fee, the limits and the cooldown
+        #[ink(message)]
+        #[modifiers(only_role(MINTER))]
+        pub fn airdrop(&mut self, transfers: Vec<(AccountId, Balance)>) -> Result<(), PSP22Error> {
+            let from = self.env().caller();
+            let total = self._check_batch(&transfers)?;
+
+            for (to, value) in transfers.iter() {
+                self._check_blacklist(&[*to])?;
+                self._transfer_from_to(from, *to, *value, Vec::<u8>::new())?;
+            }
+            self.env().emit_event(BatchTransfer {
+                from,
+                recipients: transfers.len() as u32,
+                total,
+                fee_exempt: true,
+            });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        #[modifiers(only_role(FEE_MANAGER))]
+        pub fn set_max_batch_length(&mut self, max_batch_length: u32) -> Result<(), PSP22Error> {
+            self.max_batch_length = max_batch_length;
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_max_batch_length(&self) -> u32 {
+            self.max_batch_length
+        }
+
+        /// Check the length of the batch and return the total amount sent
+        fn _check_batch(&self, transfers: &[(AccountId, Balance)]) -> Result<Balance, Psp22FeeError> {
+            if transfers.is_empty() || transfers.len() > self.max_batch_length as usize {
+                return Err(Psp22FeeError::InvalidBatchLength);
+            }
+
+            transfers
+                .iter()
+                .try_fold(0, |total: Balance, (_, value)| total.checked_add(*value))
+                .ok_or(Psp22FeeError::Overflow)
+        }
     }
 
     impl Psp22Fee for Contract {}
+    impl Psp22FeeInternal for Contract {
+        fn _emit_fee_charged_event(&self, from: AccountId, to: AccountId, fee: Balance) {
+            self.env().emit_event(FeeCharged { from, to, fee });
+        }
+
+        fn _emit_fee_updated_event(&self, fee: u128, buy_fee: u128, sell_fee: u128) {
+            self.env().emit_event(FeeUpdated { fee, buy_fee, sell_fee });
+        }
+
+        fn _emit_max_tx_updated_event(&self, max_tx: Limit) {
+            self.env().emit_event(MaxTxUpdated { max_tx });
+        }
+
+        fn _emit_max_wallet_updated_event(&self, max_wallet: Limit) {
+            self.env().emit_event(MaxWalletUpdated { max_wallet });
+        }
+
+        fn _emit_trading_enabled_event(&self, block: BlockNumber) {
+            self.env().emit_event(TradingEnabled { block });
+        }
+
+        fn _emit_blacklist_updated_event(&self, account: AccountId, blacklisted: bool) {
+            self.env().emit_event(BlacklistUpdated { account, blacklisted });
+        }
+    }
+    impl Psp22Permit for Contract {}
+    impl FlashLender for Contract {}
+    impl FlashLenderInternal for Contract {
+        fn _emit_flash_loan_event(&self, receiver: AccountId, amount: Balance, fee: Balance) {
+            self.env().emit_event(FlashLoan { receiver, amount, fee });
+        }
+
+        fn _max_supply(&self) -> Balance {
+            self.cap
+        }
+    }
+    impl Dividend for Contract {}
+    impl DividendInternal for Contract {
+        fn _emit_dividends_distributed_event(&self, from: AccountId, amount: Balance) {
+            self.env().emit_event(DividendsDistributed { from, amount });
+        }
+
+        fn _emit_dividend_withdrawn_event(&self, to: AccountId, amount: Balance) {
+            self.env().emit_event(DividendWithdrawn { to, amount });
+        }
+    }
+    impl Psp22Votes for Contract {}
+    impl Psp22VotesInternal for Contract {
+        fn _emit_delegate_changed_event(&self, delegator: AccountId, from_delegate: AccountId, to_delegate: AccountId) {
+            self.env().emit_event(DelegateChanged {
+                delegator,
+                from_delegate,
+                to_delegate,
+            });
+        }
+
+        fn _emit_delegate_votes_changed_event(&self, delegate: AccountId, previous_votes: Balance, new_votes: Balance) {
+            self.env().emit_event(DelegateVotesChanged {
+                delegate,
+                previous_votes,
+                new_votes,
+            });
+        }
+    }
     impl PSP22 for Contract {
         #[ink(message)]
         fn transfer(&mut self, to: AccountId, value: Balance, data: Vec<u8>) -> Result<(), PSP22Error> {
             let from = self.env().caller();
+            self._transfer_with_fee(from, to, value, data)?;
+            Ok(())
+        }
 
-            let balance_wallet = self._balance_of(&to);
+        #[ink(message)]
+        fn transfer_from(
+            &mut self,
+            from: AccountId,
+            to: AccountId,
+            value: Balance,
+            data: Vec<u8>,
+        ) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            let allowance = self._allowance(&from, &caller);
 
-            if value > self.psp22_fee.max_tx {
-                return Err(PSP22Error::InsufficientBalance);
+            if allowance < value {
+                return Err(PSP22Error::InsufficientAllowance);
             }
 
-            if value + balance_wallet > self.psp22_fee.max_wallet {
-                return Err(PSP22Error::InsufficientBalance);
+            self._approve_from_to(from, caller, allowance - value)?;
+            self._transfer_with_fee(from, to, value, data)?;
+            Ok(())
+        }
+    }
+
+    impl PSP22Mintable for Contract {
+        #[ink(message)]
+        #[modifiers(only_role(MINTER))]
+        fn mint(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+            let supply = self.total_supply().checked_add(amount).ok_or(Psp22FeeError::Overflow)?;
+            if supply > self.cap {
+                return Err(Psp22FeeError::CapExceeded.into());
             }
 
-            let is_tax = to != ZERO_ADDRESS.into() && from != ZERO_ADDRESS.into();
+            self._check_blacklist(&[account])?;
+            self._mint_to(account, amount)
+        }
+    }
+
+    impl PSP22Burnable for Contract {
+        /// Burns from the caller or, with enough allowance, from `account` on its behalf
+        #[ink(message)]
+        fn burn(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+            let caller = self.env().caller();
+            if caller != account {
+                let allowance = self._allowance(&account, &caller);
+                if allowance < amount {
+                    return Err(PSP22Error::InsufficientAllowance);
+                }
+                self._approve_from_to(account, caller, allowance - amount)?;
+            }
+
+            self._burn_from(account, amount)
+        }
+    }
+
+    // Override event emission methods
+    impl psp22::Internal for Contract {
+        fn _emit_transfer_event(&self, from: Option<AccountId>, to: Option<AccountId>, value: Balance) {
+            self.env().emit_event(Transfer { from, to, value });
+        }
+
+        fn _emit_approval_event(&self, owner: AccountId, spender: AccountId, value: Balance) {
+            self.env().emit_event(Approval { owner, spender, value });
+        }
+
+        // Transfers, mints and burns are halted while the contract is paused.
+        // Dividends are settled before the balances change
+        fn _before_token_transfer(
+            &mut self,
+            from: Option<&AccountId>,
+            to: Option<&AccountId>,
+            _amount: &Balance,
+        ) -> Result<(), PSP22Error> {
+            if self.paused() {
+                return Err(PausableError::Paused.into());
+            }
+            self._before_dividend_transfer(from, to)
+        }
+
+        // Keep the vote checkpoints in sync with every balance change
+        fn _after_token_transfer(
+            &mut self,
+            from: Option<&AccountId>,
+            to: Option<&AccountId>,
+            amount: &Balance,
+        ) -> Result<(), PSP22Error> {
+            self._after_votes_transfer(from, to, *amount)
+        }
+
+        // Covers `approve`, `increase_allowance`, `decrease_allowance` and the spender of `transfer_from`
+        fn _approve_from_to(
+            &mut self,
+            owner: AccountId,
+            spender: AccountId,
+            amount: Balance,
+        ) -> Result<(), PSP22Error> {
+            self._check_blacklist(&[owner, spender])?;
+
+            if owner == ZERO_ADDRESS.into() {
+                return Err(PSP22Error::ZeroSenderAddress);
+            }
+            if spender == ZERO_ADDRESS.into() {
+                return Err(PSP22Error::ZeroRecipientAddress);
+            }
+
+            self.psp22.allowances.insert(&(&owner, &spender), &amount);
+            self._emit_approval_event(owner, spender, amount);
+            Ok(())
+        }
+    }
+
+    impl pausable::Internal for Contract {
+        fn _emit_paused_event(&self, account: AccountId) {
+            self.env().emit_event(Paused { account });
+        }
+
+        fn _emit_unpaused_event(&self, account: AccountId) {
+            self.env().emit_event(Unpaused { account });
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::hash::{Blake2x256, HashOutput};
+        use ink::env::{test, DefaultEnvironment};
+        use logics_pkg::traits::error::Error;
+        use secp256k1::{Message, PublicKey, Secp256k1, SecretKey};
+
+        const SUPPLY: Balance = 1_000_000;
+
+        /// Alice deploys with a 1% fee and a 5% max transaction and sends 200_000 to Bob,
+        /// the contract lives at Django's address. Trading is still disabled
+        fn deploy() -> (Contract, test::DefaultAccounts<DefaultEnvironment>) {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+
+            let mut contract = Contract::new(SUPPLY, 2 * SUPPLY, None, None, 18, 10_000, 500, 100);
+            assert!(contract.transfer(accounts.bob, 200_000, Vec::new()).is_ok());
+
+            (contract, accounts)
+        }
+
+        fn setup() -> (Contract, test::DefaultAccounts<DefaultEnvironment>) {
+            let (mut contract, accounts) = deploy();
+            assert!(contract.enable_trading().is_ok());
+
+            (contract, accounts)
+        }
+
+        #[ink::test]
+        fn transfer_and_transfer_from_charge_the_same_fee() {
+            let (mut contract, accounts) = setup();
+            let treasury = contract.balance_of(accounts.alice);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.transfer(accounts.charlie, 10_000, Vec::new()).is_ok());
+            assert_eq!(contract.balance_of(accounts.charlie), 9_900);
+            assert_eq!(contract.balance_of(accounts.alice), treasury + 100);
+
+            assert!(contract.approve(accounts.eve, 10_000).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(contract
+                .transfer_from(accounts.bob, accounts.frank, 10_000, Vec::new())
+                .is_ok());
+            assert_eq!(contract.balance_of(accounts.frank), 9_900);
+            assert_eq!(contract.balance_of(accounts.alice), treasury + 200);
+            assert_eq!(contract.balance_of(accounts.bob), 180_000);
+            assert_eq!(contract.allowance(accounts.bob, accounts.eve), 0);
+        }
+
+        #[ink::test]
+        fn transfer_and_transfer_from_reject_the_same_amounts() {
+            let (mut contract, accounts) = setup();
+            let max_tx = contract.get_max_tx();
+            assert_eq!(max_tx, 50_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let direct = contract.transfer(accounts.charlie, max_tx + 1, Vec::new());
+            assert!(contract.approve(accounts.eve, max_tx + 1).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            let delegated = contract.transfer_from(accounts.bob, accounts.charlie, max_tx + 1, Vec::new());
+
+            assert_eq!(direct, Err(Psp22FeeError::MaxTxExceeded.into()));
+            assert_eq!(delegated, direct);
+            assert_eq!(contract.balance_of(accounts.bob), 200_000);
+            assert_eq!(contract.balance_of(accounts.charlie), 0);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.transfer(accounts.charlie, max_tx, Vec::new()).is_ok());
+            assert!(contract.approve(accounts.eve, max_tx).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert!(contract
+                .transfer_from(accounts.bob, accounts.frank, max_tx, Vec::new())
+                .is_ok());
+            assert_eq!(
+                contract.balance_of(accounts.charlie),
+                contract.balance_of(accounts.frank)
+            );
+        }
+
+        #[ink::test]
+        fn zero_address_fee_recipient_is_rejected() {
+            let (mut contract, accounts) = setup();
+            let recipients = Vec::from([
+                (FeeRecipient::Account(accounts.charlie), 5_000),
+                (FeeRecipient::Account(ZERO_ADDRESS.into()), 5_000),
+            ]);
+
+            assert_eq!(
+                contract.set_fee_recipients(recipients),
+                Err(Psp22FeeError::InvalidFeeRecipients)
+            );
+            assert_eq!(
+                contract.get_fee_recipients(),
+                Vec::from([(FeeRecipient::Account(accounts.alice), psp22_fee::FEE_DENOMINATOR)])
+            );
+        }
+
+        #[ink::test]
+        fn blacklisted_accounts_can_not_send_receive_or_spend() {
+            let (mut contract, accounts) = setup();
+            let blacklisted = Err(PSP22Error::from(Psp22FeeError::Blacklisted));
+            assert!(contract.transfer(accounts.charlie, 10_000, Vec::new()).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.approve(accounts.eve, 10_000).is_ok());
+            assert!(contract.approve(accounts.charlie, 10_000).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert!(contract.approve(accounts.eve, 10_000).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(contract.add_to_blacklist(Vec::from([accounts.charlie])).is_ok());
+
+            // Sender
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(contract.transfer(accounts.bob, 1_000, Vec::new()), blacklisted);
+            assert_eq!(contract.approve(accounts.eve, 1_000), blacklisted);
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                contract.transfer_from(accounts.charlie, accounts.frank, 1_000, Vec::new()),
+                blacklisted
+            );
+
+            // Receiver
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.transfer(accounts.charlie, 1_000, Vec::new()), blacklisted);
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                contract.transfer_from(accounts.bob, accounts.charlie, 1_000, Vec::new()),
+                blacklisted
+            );
+
+            // Spender
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.approve(accounts.charlie, 1_000), blacklisted);
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert_eq!(
+                contract.transfer_from(accounts.bob, accounts.frank, 1_000, Vec::new()),
+                blacklisted
+            );
+
+            assert_eq!(contract.balance_of(accounts.charlie), 10_000);
+            assert_eq!(contract.balance_of(accounts.frank), 0);
+        }
+
+        #[ink::test]
+        fn non_exempt_accounts_cannot_trade_before_launch() {
+            let (mut contract, accounts) = deploy();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.transfer(accounts.charlie, 1_000, Vec::new()),
+                Err(Psp22FeeError::TradingDisabled.into())
+            );
+            // Exempt accounts can still move tokens, e.g. to seed the pool
+            assert!(contract.transfer(accounts.alice, 1_000, Vec::new()).is_ok());
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(contract.enable_trading().is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.transfer(accounts.charlie, 1_000, Vec::new()).is_ok());
+        }
+
+        #[ink::test]
+        fn launch_protection_applies_only_during_the_window() {
+            let (mut contract, accounts) = deploy();
+            // 3 blocks with a 50% fee and a 1% max transaction
+            assert!(contract.set_launch_protection(3, 5_000, 100).is_ok());
+            assert!(contract.enable_trading().is_ok());
+            assert!(contract.is_launch_window());
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.transfer(accounts.charlie, 10_001, Vec::new()),
+                Err(Psp22FeeError::MaxTxExceeded.into())
+            );
+            assert!(contract.transfer(accounts.charlie, 10_000, Vec::new()).is_ok());
+            assert_eq!(contract.balance_of(accounts.charlie), 5_000);
+
+            for _ in 0..3 {
+                test::advance_block::<DefaultEnvironment>();
+            }
+            assert!(!contract.is_launch_window());
+
+            assert!(contract.transfer(accounts.eve, 20_000, Vec::new()).is_ok());
+            assert_eq!(contract.balance_of(accounts.eve), 19_800);
+        }
+
+        /// One minute cooldown, Frank is a pair holding 100_000 tokens
+        fn setup_cooldown() -> (Contract, test::DefaultAccounts<DefaultEnvironment>) {
+            let (mut contract, accounts) = setup();
+            assert!(contract.transfer(accounts.frank, 100_000, Vec::new()).is_ok());
+            assert!(contract.add_pair(accounts.frank).is_ok());
+            assert!(contract.set_cooldown(60_000).is_ok());
+            test::set_block_timestamp::<DefaultEnvironment>(1_000);
+
+            (contract, accounts)
+        }
+
+        #[ink::test]
+        fn cooldown_limits_senders() {
+            let (mut contract, accounts) = setup_cooldown();
+            let cooldown_active = Err(PSP22Error::from(Psp22FeeError::CooldownActive));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.transfer(accounts.charlie, 1_000, Vec::new()).is_ok());
+            assert_eq!(contract.next_allowed_transfer(accounts.bob), 61_000);
+            // Receiving does not start a cooldown
+            assert_eq!(contract.next_allowed_transfer(accounts.charlie), 0);
+
+            assert_eq!(contract.transfer(accounts.charlie, 1_000, Vec::new()), cooldown_active);
+            test::set_block_timestamp::<DefaultEnvironment>(60_999);
+            assert_eq!(contract.transfer(accounts.charlie, 1_000, Vec::new()), cooldown_active);
+            test::set_block_timestamp::<DefaultEnvironment>(61_000);
+            assert!(contract.transfer(accounts.charlie, 1_000, Vec::new()).is_ok());
+            assert_eq!(contract.next_allowed_transfer(accounts.bob), 121_000);
+        }
+
+        #[ink::test]
+        fn cooldown_limits_buyers_but_not_pairs_or_exempt_accounts() {
+            let (mut contract, accounts) = setup_cooldown();
+            let cooldown_active = Err(PSP22Error::from(Psp22FeeError::CooldownActive));
+
+            // A buy is charged to the buyer, the pair can keep selling to others
+            test::set_caller::<DefaultEnvironment>(accounts.frank);
+            assert!(contract.transfer(accounts.eve, 1_000, Vec::new()).is_ok());
+            assert_eq!(contract.next_allowed_transfer(accounts.eve), 61_000);
+            assert_eq!(contract.next_allowed_transfer(accounts.frank), 0);
+            assert_eq!(contract.transfer(accounts.eve, 1_000, Vec::new()), cooldown_active);
+            assert!(contract.transfer(accounts.charlie, 1_000, Vec::new()).is_ok());
+
+            test::set_caller::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(contract.transfer(accounts.frank, 500, Vec::new()), cooldown_active);
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(contract.transfer(accounts.bob, 1_000, Vec::new()).is_ok());
+            assert!(contract.transfer(accounts.bob, 1_000, Vec::new()).is_ok());
+            assert_eq!(contract.next_allowed_transfer(accounts.alice), 0);
+        }
+
+        #[ink::test]
+        fn only_pausers_can_pause() {
+            let (mut contract, accounts) = setup();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                PauseManager::pause(&mut contract),
+                Err(Error::AccessControlError(AccessControlError::MissingRole))
+            );
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(contract.grant_role(PAUSER, accounts.bob).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(PauseManager::pause(&mut contract).is_ok());
+            assert_eq!(
+                contract.transfer(accounts.charlie, 1_000, Vec::new()),
+                Err(PausableError::Paused.into())
+            );
+            assert!(PauseManager::unpause(&mut contract).is_ok());
+            assert!(contract.transfer(accounts.charlie, 1_000, Vec::new()).is_ok());
+        }
+
+        #[ink::test]
+        fn mint_is_capped() {
+            let (mut contract, accounts) = setup();
+
+            assert!(contract.mint(accounts.charlie, SUPPLY).is_ok());
+            assert_eq!(contract.total_supply(), contract.cap());
+            assert_eq!(
+                contract.mint(accounts.charlie, 1),
+                Err(Psp22FeeError::CapExceeded.into())
+            );
+            assert_eq!(contract.balance_of(accounts.charlie), SUPPLY);
+
+            // Burning frees room under the cap again
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert!(contract.burn(accounts.charlie, 10).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(contract.mint(accounts.charlie, 10).is_ok());
+            assert_eq!(
+                contract.mint(accounts.charlie, 1),
+                Err(Psp22FeeError::CapExceeded.into())
+            );
+        }
+
+        #[ink::test]
+        fn percentage_limits_follow_the_supply() {
+            let (mut contract, accounts) = setup();
+            assert!(contract.set_max_wallet(2_000).is_ok());
+            assert_eq!(contract.get_max_tx(), 50_000);
+            assert_eq!(contract.get_max_wallet(), 200_000);
+
+            assert!(contract.mint(accounts.charlie, SUPPLY).is_ok());
+            assert_eq!(contract.get_max_tx(), 100_000);
+            assert_eq!(contract.get_max_wallet(), 400_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.transfer(accounts.eve, 100_000, Vec::new()).is_ok());
+
+            test::set_caller::<DefaultEnvironment>(accounts.charlie);
+            assert!(contract.burn(accounts.charlie, SUPPLY / 2).is_ok());
+            assert_eq!(contract.get_max_tx(), 75_000);
+            assert_eq!(contract.get_max_wallet(), 300_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.transfer(accounts.eve, 75_001, Vec::new()),
+                Err(Psp22FeeError::MaxTxExceeded.into())
+            );
+            assert!(contract.transfer(accounts.eve, 75_000, Vec::new()).is_ok());
+        }
+
+        #[ink::test]
+        fn votes_follow_balances() {
+            let (mut contract, accounts) = setup();
+            assert_eq!(contract.get_votes(accounts.bob), 200_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.delegate(accounts.eve).is_ok());
+            assert!(contract.transfer(accounts.charlie, 10_000, Vec::new()).is_ok());
+            assert_eq!(contract.get_votes(accounts.bob), 0);
+            assert_eq!(contract.get_votes(accounts.eve), 190_000);
+            assert_eq!(contract.get_votes(accounts.charlie), 9_900);
+            assert_eq!(contract.get_votes(accounts.alice), contract.balance_of(accounts.alice));
+        }
+
+        /// Account of the ECDSA key used to sign permits, derived the same way as in `_recover_signer`
+        fn permit_signer() -> (SecretKey, AccountId) {
+            let secret = SecretKey::from_slice(&[0x42; 32]).expect("Valid secret key");
+            let public = PublicKey::from_secret_key(&Secp256k1::new(), &secret);
+
+            let mut account = <Blake2x256 as HashOutput>::Type::default();
+            ink::env::hash_bytes::<Blake2x256>(&public.serialize(), &mut account);
+
+            (secret, AccountId::from(account))
+        }
+
+        fn sign_permit(
+            contract: &Contract,
+            secret: &SecretKey,
+            owner: AccountId,
+            spender: AccountId,
+            value: Balance,
+            deadline: Timestamp,
+        ) -> [u8; 65] {
+            let hash = contract.permit_hash(owner, spender, value, deadline);
+            let message = Message::from_slice(&hash).expect("Hash is 32 bytes");
+            let (recovery_id, compact) = Secp256k1::new()
+                .sign_ecdsa_recoverable(&message, secret)
+                .serialize_compact();
+
+            let mut signature = [0u8; 65];
+            signature[..64].copy_from_slice(&compact);
+            signature[64] = recovery_id.to_i32() as u8;
+            signature
+        }
+
+        fn invalid_signature() -> Result<(), PSP22Error> {
+            Err(PSP22Error::Custom(String::from("PM::InvalidSignature")))
+        }
+
+        #[ink::test]
+        fn permit_can_not_be_replayed() {
+            let (mut contract, accounts) = setup();
+            let (secret, owner) = permit_signer();
+
+            let signature = sign_permit(&contract, &secret, owner, accounts.bob, 100, 1_000);
+            assert!(contract.permit(owner, accounts.bob, 100, 1_000, signature).is_ok());
+            assert_eq!(contract.allowance(owner, accounts.bob), 100);
+            assert_eq!(contract.nonces(owner), 1);
+
+            assert_eq!(
+                contract.permit(owner, accounts.bob, 100, 1_000, signature),
+                invalid_signature()
+            );
+            assert_eq!(contract.nonces(owner), 1);
+        }
+
+        #[ink::test]
+        fn permit_expires_after_the_deadline() {
+            let (mut contract, accounts) = setup();
+            let (secret, owner) = permit_signer();
+            let signature = sign_permit(&contract, &secret, owner, accounts.bob, 100, 1_000);
+
+            test::set_block_timestamp::<DefaultEnvironment>(1_001);
+            assert_eq!(
+                contract.permit(owner, accounts.bob, 100, 1_000, signature),
+                Err(PSP22Error::Custom(String::from("PM::Expired")))
+            );
+
+            // The deadline itself is still valid
+            test::set_block_timestamp::<DefaultEnvironment>(1_000);
+            assert!(contract.permit(owner, accounts.bob, 100, 1_000, signature).is_ok());
+        }
+
+        #[ink::test]
+        fn permit_binds_spender_and_value() {
+            let (mut contract, accounts) = setup();
+            let (secret, owner) = permit_signer();
+            let signature = sign_permit(&contract, &secret, owner, accounts.bob, 100, 1_000);
+
+            assert_eq!(
+                contract.permit(owner, accounts.charlie, 100, 1_000, signature),
+                invalid_signature()
+            );
+            assert_eq!(
+                contract.permit(owner, accounts.bob, 101, 1_000, signature),
+                invalid_signature()
+            );
+            assert_eq!(contract.allowance(owner, accounts.charlie), 0);
+            assert_eq!(contract.allowance(owner, accounts.bob), 0);
+            assert_eq!(contract.nonces(owner), 0);
+        }
+
+        #[ink::test]
+        fn permit_binds_the_contract_address() {
+            let (mut contract, accounts) = setup();
+            let (secret, owner) = permit_signer();
+            let signature = sign_permit(&contract, &secret, owner, accounts.bob, 100, 1_000);
+
+            // Same code and state deployed at another address
+            test::set_callee::<DefaultEnvironment>(accounts.eve);
+            assert_eq!(
+                contract.permit(owner, accounts.bob, 100, 1_000, signature),
+                invalid_signature()
+            );
+
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            assert!(contract.permit(owner, accounts.bob, 100, 1_000, signature).is_ok());
+        }
+
+        /// Alice sends 100_000 to Charlie, so Bob and Charlie hold all of the 300_000 tokens earning dividends.
+        /// The contract holds enough native currency to pay them
+        fn setup_dividends() -> (Contract, test::DefaultAccounts<DefaultEnvironment>) {
+            let (mut contract, accounts) = setup();
+            assert!(contract.transfer(accounts.charlie, 100_000, Vec::new()).is_ok());
+            test::set_account_balance::<DefaultEnvironment>(accounts.django, 10_000_000);
+
+            (contract, accounts)
+        }
+
+        fn distribute(contract: &mut Contract, amount: Balance) {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            test::set_value_transferred::<DefaultEnvironment>(amount);
+            assert!(contract.distribute_native().is_ok());
+            test::set_value_transferred::<DefaultEnvironment>(0);
+        }
+
+        #[ink::test]
+        fn dividends_are_shared_pro_rata() {
+            let (mut contract, accounts) = setup_dividends();
+            assert!(contract.is_excluded_from_dividends(accounts.alice));
+            assert!(contract.is_excluded_from_dividends(accounts.django));
+
+            distribute(&mut contract, 300_000);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.bob), 200_000);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.charlie), 100_000);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.alice), 0);
+            assert_eq!(contract.total_dividends_distributed(), 300_000);
+        }
+
+        #[ink::test]
+        fn later_holders_do_not_earn_past_dividends() {
+            let (mut contract, accounts) = setup_dividends();
+            distribute(&mut contract, 300_000);
+
+            // Eve receives 49_500, the fee goes to the excluded treasury
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.transfer(accounts.eve, 50_000, Vec::new()).is_ok());
+            assert_eq!(contract.withdrawable_dividend_of(accounts.eve), 0);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.bob), 200_000);
+
+            // One per token for the 150_000 + 100_000 + 49_500 eligible tokens
+            distribute(&mut contract, 299_500);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.eve), 49_500);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.bob), 350_000);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.charlie), 200_000);
+        }
+
+        #[ink::test]
+        fn excluded_accounts_do_not_earn_dividends() {
+            let (mut contract, accounts) = setup_dividends();
+            distribute(&mut contract, 300_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(
+                contract.exclude_from_dividends(accounts.bob),
+                Err(PSP22Error::from(AccessControlError::MissingRole))
+            );
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(contract.exclude_from_dividends(accounts.bob).is_ok());
+
+            // Bob keeps what he earned before, Charlie gets the whole distribution
+            distribute(&mut contract, 100_000);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.bob), 200_000);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.charlie), 200_000);
+
+            assert!(contract.include_in_dividends(accounts.bob).is_ok());
+            assert!(!contract.is_excluded_from_dividends(accounts.bob));
+            distribute(&mut contract, 300_000);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.bob), 400_000);
+            assert_eq!(contract.withdrawable_dividend_of(accounts.charlie), 300_000);
+        }
+
+        #[ink::test]
+        fn withdraw_dividend_pays_everything_earned() {
+            let (mut contract, accounts) = setup_dividends();
+            distribute(&mut contract, 300_000);
+            let native_balance = test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap_or(0);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.withdraw_dividend(), Ok(200_000));
+            assert_eq!(contract.withdrawable_dividend_of(accounts.bob), 0);
+            assert_eq!(contract.withdrawn_dividend_of(accounts.bob), 200_000);
+            assert_eq!(
+                test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap_or(0),
+                native_balance + 200_000
+            );
+            assert_eq!(contract.withdraw_dividend(), Ok(0));
+        }
+
+        #[ink::test]
+        fn batch_administration_requires_roles() {
+            let (mut contract, accounts) = setup();
+            let missing_role = Err(PSP22Error::from(AccessControlError::MissingRole));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.airdrop(Vec::from([(accounts.charlie, 1_000)])), missing_role);
+            assert_eq!(contract.set_max_batch_length(1), missing_role);
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(contract.grant_role(MINTER, accounts.bob).is_ok());
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(contract.airdrop(Vec::from([(accounts.charlie, 1_000)])).is_ok());
+            assert_eq!(contract.balance_of(accounts.charlie), 1_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert!(contract.set_max_batch_length(1).is_ok());
+            assert_eq!(contract.get_max_batch_length(), 1);
+        }
+
+        #[ink::test]
+        fn batch_transfer_counts_as_one_send_for_the_cooldown() {
+            let (mut contract, accounts) = setup();
+            assert!(contract.set_cooldown(60_000).is_ok());
+            test::set_block_timestamp::<DefaultEnvironment>(1_000);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let transfers = Vec::from([
+                (accounts.charlie, 10_000),
+                (accounts.eve, 10_000),
+                (accounts.frank, 10_000),
+            ]);
+            assert!(contract.batch_transfer(transfers).is_ok());
+            for account in [accounts.charlie, accounts.eve, accounts.frank] {
+                assert_eq!(contract.balance_of(account), 9_900);
+            }
+            assert_eq!(contract.next_allowed_transfer(accounts.bob), 61_000);
+
+            assert_eq!(
+                contract.batch_transfer(Vec::from([(accounts.charlie, 1_000)])),
+                Err(Psp22FeeError::CooldownActive.into())
+            );
+        }
+
+        #[ink::test]
+        fn batch_transfer_is_all_or_nothing() {
+            let (mut contract, accounts) = setup();
+            assert!(contract.add_to_blacklist(Vec::from([accounts.eve])).is_ok());
+            let holders = [
+                accounts.alice,
+                accounts.bob,
+                accounts.charlie,
+                accounts.eve,
+                accounts.frank,
+            ];
+            let balances = holders.map(|account| contract.balance_of(account));
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let blacklisted = Vec::from([
+                (accounts.charlie, 10_000),
+                (accounts.eve, 10_000),
+                (accounts.frank, 10_000),
+            ]);
+            assert_eq!(
+                contract.batch_transfer(blacklisted),
+                Err(Psp22FeeError::Blacklisted.into())
+            );
+            let above_max_tx = Vec::from([
+                (accounts.charlie, 10_000),
+                (accounts.frank, 50_001),
+                (accounts.charlie, 10_000),
+            ]);
+            assert_eq!(
+                contract.batch_transfer(above_max_tx),
+                Err(Psp22FeeError::MaxTxExceeded.into())
+            );
+            let above_balance = Vec::from([(accounts.charlie, 10_000), (accounts.frank, 190_001)]);
+            assert_eq!(
+                contract.batch_transfer(above_balance),
+                Err(PSP22Error::InsufficientBalance)
+            );
+
+            assert_eq!(holders.map(|account| contract.balance_of(account)), balances);
+        }
+
+        #[ink::test]
+        fn flash_loans_are_capped() {
+            let (mut contract, accounts) = setup();
+            let token = accounts.django;
+
+            assert_eq!(contract.max_flash_loan(token), contract.cap() - contract.total_supply());
+            assert_eq!(contract.max_flash_loan(accounts.eve), 0);
+            assert_eq!(
+                contract.flash_loan(accounts.bob, token, SUPPLY + 1, Vec::new()),
+                Err(PSP22Error::Custom(String::from("FL::AmountTooHigh")))
+            );
+            assert_eq!(contract.total_supply(), SUPPLY);
+        }
+    }
+
+    #[cfg(all(test, feature = "e2e-tests"))]
+    mod e2e_tests {
+        use super::*;
+        use flash_borrower::SampleFlashBorrowerRef;
+        use ink_e2e::{build_message, AccountKeyring};
+        use mock_reentrant_borrower::MockReentrantBorrowerRef;
+        use mock_router::MockRouterRef;
+
+        type E2EResult<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+        /// Every taxed transfer of 10_000 books a fee of 100 as liquidity, the swap runs once 150 are accumulated
+        #[ink_e2e::test(additional_contracts = "../mock_router/Cargo.toml")]
+        async fn swap_and_liquify_on_threshold_and_on_demand(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let alice = ink_e2e::account_id(AccountKeyring::Alice);
+            let bob = ink_e2e::account_id(AccountKeyring::Bob);
+            let charlie = ink_e2e::account_id(AccountKeyring::Charlie);
+
+            let constructor = ContractRef::new(1_000_000, 2_000_000, None, None, 18, 10_000, 10_000, 100);
+            let token = client
+                .instantiate("my_contracts", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("token instantiate failed")
+                .account_id;
+            let router = client
+                .instantiate("mock_router", &ink_e2e::alice(), MockRouterRef::new(), 0, None)
+                .await
+                .expect("router instantiate failed")
+                .account_id;
+
+            let set_router = build_message::<ContractRef>(token.clone()).call(|contract| contract.set_router(router));
+            client
+                .call(&ink_e2e::alice(), set_router, 0, None)
+                .await
+                .expect("set_router failed");
+            let set_threshold =
+                build_message::<ContractRef>(token.clone()).call(|contract| contract.set_swap_threshold(150));
+            client
+                .call(&ink_e2e::alice(), set_threshold, 0, None)
+                .await
+                .expect("set_swap_threshold failed");
+            let set_recipients = build_message::<ContractRef>(token.clone()).call(|contract| {
+                contract.set_fee_recipients(Vec::from([(FeeRecipient::Liquidity, psp22_fee::FEE_DENOMINATOR)]))
+            });
+            client
+                .call(&ink_e2e::alice(), set_recipients, 0, None)
+                .await
+                .expect("set_fee_recipients failed");
+            let enable = build_message::<ContractRef>(token.clone()).call(|contract| contract.enable_trading());
+            client
+                .call(&ink_e2e::alice(), enable, 0, None)
+                .await
+                .expect("enable_trading failed");
+            let fund = build_message::<ContractRef>(token.clone())
+                .call(|contract| contract.transfer(bob, 100_000, Vec::new()));
+            client
+                .call(&ink_e2e::alice(), fund, 0, None)
+                .await
+                .expect("fund failed");
+
+            let accumulated =
+                build_message::<ContractRef>(token.clone()).call(|contract| contract.get_accumulated_liquidity());
+            let liquidity = build_message::<MockRouterRef>(router.clone()).call(|router| router.liquidity_of(alice));
+
+            // Below the threshold the fee only accumulates
+            for _ in 0..2 {
+                let transfer = build_message::<ContractRef>(token.clone())
+                    .call(|contract| contract.transfer(charlie, 10_000, Vec::new()));
+                client
+                    .call(&ink_e2e::bob(), transfer, 0, None)
+                    .await
+                    .expect("transfer failed");
+            }
+            let result = client.call_dry_run(&ink_e2e::alice(), &accumulated, 0, None).await;
+            assert_eq!(result.return_value(), 200);
+            let result = client.call_dry_run(&ink_e2e::alice(), &liquidity, 0, None).await;
+            assert_eq!(result.return_value(), 0);
+
+            // The next taxed transfer swaps what was accumulated before its own fee
+            let transfer = build_message::<ContractRef>(token.clone())
+                .call(|contract| contract.transfer(charlie, 10_000, Vec::new()));
+            client
+                .call(&ink_e2e::bob(), transfer, 0, None)
+                .await
+                .expect("transfer failed");
+            let result = client.call_dry_run(&ink_e2e::alice(), &liquidity, 0, None).await;
+            assert_eq!(result.return_value(), 200);
+            let result = client.call_dry_run(&ink_e2e::alice(), &accumulated, 0, None).await;
+            assert_eq!(result.return_value(), 100);
+
+            // The fee manager swaps the rest below the threshold
+            let swap = build_message::<ContractRef>(token.clone()).call(|contract| contract.swap_and_liquify());
+            client
+                .call(&ink_e2e::alice(), swap, 0, None)
+                .await
+                .expect("swap failed");
+            let result = client.call_dry_run(&ink_e2e::alice(), &liquidity, 0, None).await;
+            assert_eq!(result.return_value(), 300);
+            let result = client.call_dry_run(&ink_e2e::alice(), &accumulated, 0, None).await;
+            assert_eq!(result.return_value(), 0);
+
+            // Only the fee manager can trigger it
+            let swap = build_message::<ContractRef>(token.clone()).call(|contract| contract.swap_and_liquify());
+            assert!(client.call(&ink_e2e::bob(), swap, 0, None).await.is_err());
+
+            Ok(())
+        }
+
+        /// The sample borrower pays the fee from its own balance, without one the loan is not repaid
+        #[ink_e2e::test(additional_contracts = "../flash_borrower/Cargo.toml")]
+        async fn flash_loan_must_be_repaid(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let constructor = ContractRef::new(1_000_000, 2_000_000, None, None, 18, 10_000, 10_000, 0);
+            let token = client
+                .instantiate("my_contracts", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("token instantiate failed")
+                .account_id;
+            let borrower = client
+                .instantiate(
+                    "flash_borrower",
+                    &ink_e2e::alice(),
+                    SampleFlashBorrowerRef::new(token),
+                    0,
+                    None,
+                )
+                .await
+                .expect("borrower instantiate failed")
+                .account_id;
+
+            // 1% fee
+            let set_fee = build_message::<ContractRef>(token.clone()).call(|contract| contract.set_flash_fee(100));
+            client
+                .call(&ink_e2e::alice(), set_fee, 0, None)
+                .await
+                .expect("set_flash_fee failed");
+
+            let supply = build_message::<ContractRef>(token.clone()).call(|contract| contract.total_supply());
+            let balance = build_message::<ContractRef>(token.clone()).call(|contract| contract.balance_of(borrower));
+
+            let borrow = build_message::<SampleFlashBorrowerRef>(borrower.clone())
+                .call(|borrower| borrower.flash_borrow(10_000, Vec::new()));
+            assert!(client.call(&ink_e2e::alice(), borrow, 0, None).await.is_err());
+            let result = client.call_dry_run(&ink_e2e::alice(), &supply, 0, None).await;
+            assert_eq!(result.return_value(), 1_000_000);
+
+            let fund = build_message::<ContractRef>(token.clone())
+                .call(|contract| contract.transfer(borrower, 100, Vec::new()));
+            client
+                .call(&ink_e2e::alice(), fund, 0, None)
+                .await
+                .expect("fund failed");
+
+            // Only the owner of the borrower can take loans through it
+            let borrow = build_message::<SampleFlashBorrowerRef>(borrower.clone())
+                .call(|borrower| borrower.flash_borrow(10_000, Vec::new()));
+            assert!(client.call(&ink_e2e::bob(), borrow, 0, None).await.is_err());
+
+            let borrow = build_message::<SampleFlashBorrowerRef>(borrower.clone())
+                .call(|borrower| borrower.flash_borrow(10_000, Vec::new()));
+            client
+                .call(&ink_e2e::alice(), borrow, 0, None)
+                .await
+                .expect("flash_borrow failed");
+            let result = client.call_dry_run(&ink_e2e::alice(), &balance, 0, None).await;
+            assert_eq!(result.return_value(), 0);
+            let result = client.call_dry_run(&ink_e2e::alice(), &supply, 0, None).await;
+            assert_eq!(result.return_value(), 1_000_000 - 100);
+
+            Ok(())
+        }
+
+        /// The borrower asks for a second loan from inside `on_flash_loan` and repays the first one
+        #[ink_e2e::test(additional_contracts = "../mock_reentrant_borrower/Cargo.toml")]
+        async fn nested_flash_loan_is_rejected(mut client: ink_e2e::Client<C, E>) -> E2EResult<()> {
+            let constructor = ContractRef::new(1_000_000, 2_000_000, None, None, 18, 10_000, 10_000, 0);
+            let token = client
+                .instantiate("my_contracts", &ink_e2e::alice(), constructor, 0, None)
+                .await
+                .expect("token instantiate failed")
+                .account_id;
+            let borrower = client
+                .instantiate(
+                    "mock_reentrant_borrower",
+                    &ink_e2e::alice(),
+                    MockReentrantBorrowerRef::new(),
+                    0,
+                    None,
+                )
+                .await
+                .expect("borrower instantiate failed")
+                .account_id;
+
+            let loan = build_message::<ContractRef>(token.clone())
+                .call(|contract| contract.flash_loan(borrower, token, 10_000, Vec::new()));
+            client
+                .call(&ink_e2e::alice(), loan, 0, None)
+                .await
+                .expect("flash_loan failed");
+
+            let nested_error =
+                build_message::<MockReentrantBorrowerRef>(borrower.clone()).call(|borrower| borrower.nested_error());
+            let result = client.call_dry_run(&ink_e2e::alice(), &nested_error, 0, None).await;
+            assert_eq!(result.return_value(), Some(String::from("FL::Reentrancy")));
 
-            let tax = if is_tax { (value * self.psp22_fee.fee) / 100 } else { 0 };
+            let supply = build_message::<ContractRef>(token.clone()).call(|contract| contract.total_supply());
+            let result = client.call_dry_run(&ink_e2e::alice(), &supply, 0, None).await;
+            assert_eq!(result.return_value(), 1_000_000);
 
-            self._transfer_from_to(from, self.ownable.owner, tax, data.clone())?;
-            self._transfer_from_to(from, to, value - tax, data)?;
             Ok(())
         }
     }
@@ -13,9 +13,11 @@ pub mod psp34_nft {
     use logics_pkg::impls::psp34_artzero_support::admin::*;
     use logics_pkg::impls::psp34_artzero_support::psp34_traits::psp34_traits::InternalTraits;
     use logics_pkg::impls::psp34_artzero_support::psp34_traits::*;
-    use logics_pkg::traits::{admin::*, error::Error, psp34_traits::*};
+    use logics_pkg::traits::{admin::*, error::Error, pause_manager::*, psp34_traits::*, roles::*};
     use openbrush::{
+        contracts::access_control::*,
         contracts::ownable::*,
+        contracts::pausable::*,
         contracts::psp34::extensions::{burnable::*, enumerable::*, metadata::*},
         modifiers,
         traits::{DefaultEnv, Storage},
@@ -31,9 +33,13 @@ pub mod psp34_nft {
         #[storage_field]
         ownable: ownable::Data,
         #[storage_field]
+        access_control: access_control::Data,
+        #[storage_field]
         manager: psp34_traits::Manager,
         #[storage_field]
         admin_data: admin::data::Data,
+        #[storage_field]
+        pausable: pausable::Data,
     }
 
     #[ink(event)]
@@ -58,13 +64,28 @@ pub mod psp34_nft {
         approved: bool,
     }
 
+    /// Event emitted when the contract is paused.
+    #[ink(event)]
+    pub struct Paused {
+        account: AccountId,
+    }
+
+    /// Event emitted when the contract is unpaused.
+    #[ink(event)]
+    pub struct Unpaused {
+        account: AccountId,
+    }
+
     impl Ownable for Psp34Nft {}
+    impl AccessControl for Psp34Nft {}
     impl PSP34 for Psp34Nft {}
     impl PSP34Metadata for Psp34Nft {}
     impl PSP34Enumerable for Psp34Nft {}
     impl Psp34Traits for Psp34Nft {}
     impl AdminTrait for Psp34Nft {}
     impl InternalTraits for Psp34Nft {}
+    impl Pausable for Psp34Nft {}
+    impl PauseManager for Psp34Nft {}
 
     impl PSP34Burnable for Psp34Nft {
         #[ink(message)]
@@ -93,6 +114,10 @@ pub mod psp34_nft {
             let mut instance = Self::default();
 
             instance._init_with_owner(instance.env().caller());
+            instance._init_with_admin(instance.env().caller());
+            for role in [METADATA_ADMIN, MINTER, TREASURER, PAUSER] {
+                instance._setup_role(role, instance.env().caller());
+            }
             instance._set_attribute(Id::U8(0), String::from("name").into_bytes(), name.into_bytes());
             instance._set_attribute(Id::U8(0), String::from("symbol").into_bytes(), symbol.into_bytes());
             instance._set_attribute(Id::U8(0), String::from("baseURI").into_bytes(), base_uri.into_bytes());
@@ -105,6 +130,7 @@ pub mod psp34_nft {
 
         /// This function let NFT Contract Owner to mint a new NFT without providing NFT Traits/Attributes
         #[ink(message, payable)]
+        #[modifiers(when_not_paused)]
         pub fn mint(&mut self, mint_amount: u64) -> Result<(), Error> {
             self.check_amount(mint_amount)?;
             self.check_value(self.env().transferred_value(), mint_amount)?;
@@ -136,5 +162,28 @@ pub mod psp34_nft {
         fn _emit_approval_event(&self, from: AccountId, to: AccountId, id: Option<Id>, approved: bool) {
             self.env().emit_event(Approval { from, to, id, approved });
         }
+
+        // Transfers, mints and burns are halted while the contract is paused
+        fn _before_token_transfer(
+            &mut self,
+            _from: Option<&AccountId>,
+            _to: Option<&AccountId>,
+            _id: &Id,
+        ) -> Result<(), PSP34Error> {
+            if self.paused() {
+                return Err(PausableError::Paused.into());
+            }
+            Ok(())
+        }
+    }
+
+    impl pausable::Internal for Psp34Nft {
+        fn _emit_paused_event(&self, account: AccountId) {
+            self.env().emit_event(Paused { account });
+        }
+
+        fn _emit_unpaused_event(&self, account: AccountId) {
+            self.env().emit_event(Unpaused { account });
+        }
     }
 }
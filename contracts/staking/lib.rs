@@ -0,0 +1,88 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// Staking pool paying a reward token per second to the stakers of a PSP22, usually the fee token.
+/// Exempting the pool from the token fee avoids taxing stakes and withdrawals, otherwise only what the pool
+/// receives is staked and withdrawals are taxed on the way out
+#[openbrush::contract]
+pub mod staking_pool {
+    use ink::codegen::{EmitEvent, Env};
+    use logics_pkg::impls::staking::staking::StakingInternal;
+    use logics_pkg::impls::staking::*;
+    use logics_pkg::traits::staking::*;
+    use openbrush::{contracts::ownable::*, traits::Storage};
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct StakingPool {
+        #[storage_field]
+        ownable: ownable::Data,
+        #[storage_field]
+        staking: staking::Data,
+    }
+
+    /// Event emitted when tokens are staked, `amount` is what the pool received.
+    #[ink(event)]
+    pub struct Staked {
+        #[ink(topic)]
+        staker: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when tokens are unstaked.
+    #[ink(event)]
+    pub struct Unstaked {
+        #[ink(topic)]
+        staker: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when rewards are paid to a staker.
+    #[ink(event)]
+    pub struct RewardClaimed {
+        #[ink(topic)]
+        staker: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when a staker withdraws its stake without rewards.
+    #[ink(event)]
+    pub struct EmergencyWithdraw {
+        #[ink(topic)]
+        staker: AccountId,
+        amount: Balance,
+    }
+
+    impl Ownable for StakingPool {}
+    impl Staking for StakingPool {}
+
+    impl StakingInternal for StakingPool {
+        fn _emit_staked_event(&self, staker: AccountId, amount: Balance) {
+            self.env().emit_event(Staked { staker, amount });
+        }
+
+        fn _emit_unstaked_event(&self, staker: AccountId, amount: Balance) {
+            self.env().emit_event(Unstaked { staker, amount });
+        }
+
+        fn _emit_reward_claimed_event(&self, staker: AccountId, amount: Balance) {
+            self.env().emit_event(RewardClaimed { staker, amount });
+        }
+
+        fn _emit_emergency_withdraw_event(&self, staker: AccountId, amount: Balance) {
+            self.env().emit_event(EmergencyWithdraw { staker, amount });
+        }
+    }
+
+    impl StakingPool {
+        /// `reward_rate` is the amount of reward tokens paid to all stakers per second once the reward is started
+        #[ink(constructor)]
+        pub fn new(staking_token: AccountId, reward_token: AccountId, reward_rate: Balance) -> Self {
+            let mut instance = Self::default();
+
+            instance._init_with_owner(instance.env().caller());
+            instance._init_staking(staking_token, reward_token, reward_rate);
+            instance
+        }
+    }
+}
@@ -0,0 +1,295 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// Timelock wallet releasing PSP22 allocations linearly after a cliff.
+/// The owner funds the contract with tokens and then adds one schedule per beneficiary. With a fee token the
+/// vesting contract has to be fee and limit exempt, otherwise every release is taxed
+#[openbrush::contract]
+pub mod vesting {
+    use ink::codegen::{EmitEvent, Env};
+    use ink::prelude::vec::Vec;
+    use logics_pkg::impls::math::mul_div;
+    use logics_pkg::traits::{admin::Psp22Ref, error::Error};
+    use openbrush::{contracts::ownable::*, modifiers, storage::Mapping, traits::Storage};
+
+    #[derive(Default, Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+    #[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+    pub struct VestingSchedule {
+        /// Timestamp from which the tokens vest
+        pub start: Timestamp,
+        /// Nothing can be released before `start + cliff`
+        pub cliff: Timestamp,
+        /// Everything is vested at `start + duration`
+        pub duration: Timestamp,
+        pub total: Balance,
+        pub released: Balance,
+        pub revocable: bool,
+        pub revoked: bool,
+    }
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct Vesting {
+        #[storage_field]
+        ownable: ownable::Data,
+        token: AccountId,
+        schedules: Mapping<AccountId, VestingSchedule>,
+        /// Tokens promised to beneficiaries and not released yet
+        total_allocated: Balance,
+    }
+
+    /// Event emitted when a vesting schedule is created.
+    #[ink(event)]
+    pub struct ScheduleAdded {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        total: Balance,
+    }
+
+    /// Event emitted when vested tokens are sent to a beneficiary.
+    #[ink(event)]
+    pub struct Released {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when the owner revokes a schedule and takes back the unvested tokens.
+    #[ink(event)]
+    pub struct Revoked {
+        #[ink(topic)]
+        beneficiary: AccountId,
+        refund: Balance,
+    }
+
+    impl Ownable for Vesting {}
+
+    impl Vesting {
+        #[ink(constructor)]
+        pub fn new(token: AccountId) -> Self {
+            let mut instance = Self::default();
+
+            instance._init_with_owner(instance.env().caller());
+            instance.token = token;
+            instance
+        }
+
+        /// Vest `total` tokens to `beneficiary`. The contract must already hold enough unallocated tokens.
+        /// Every beneficiary has at most one schedule, a second one fails with `ScheduleExists`
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn add_schedule(
+            &mut self,
+            beneficiary: AccountId,
+            total: Balance,
+            start: Timestamp,
+            cliff: Timestamp,
+            duration: Timestamp,
+            revocable: bool,
+        ) -> Result<(), Error> {
+            if self.schedules.get(&beneficiary).is_some() {
+                return Err(Error::ScheduleExists);
+            }
+            if total == 0 || duration == 0 {
+                return Err(Error::InvalidInput);
+            }
+            if cliff > duration {
+                return Err(Error::InvalidTime);
+            }
+
+            let total_allocated = self.total_allocated.checked_add(total).ok_or(Error::InvalidInput)?;
+            if Psp22Ref::balance_of(&self.token, self.env().account_id()) < total_allocated {
+                return Err(Error::NotEnoughBalance);
+            }
+
+            let schedule = VestingSchedule {
+                start,
+                cliff,
+                duration,
+                total,
+                revocable,
+                ..Default::default()
+            };
+            self.schedules.insert(&beneficiary, &schedule);
+            self.total_allocated = total_allocated;
+            self.env().emit_event(ScheduleAdded { beneficiary, total });
+
+            Ok(())
+        }
+
+        /// Amount of tokens `beneficiary` can release now
+        #[ink(message)]
+        pub fn releasable(&self, beneficiary: AccountId) -> Balance {
+            self.schedules
+                .get(&beneficiary)
+                .map(|schedule| self._vested_amount(&schedule) - schedule.released)
+                .unwrap_or(0)
+        }
+
+        /// Send the vested tokens to `beneficiary`, anyone can trigger it
+        #[ink(message)]
+        pub fn release(&mut self, beneficiary: AccountId) -> Result<Balance, Error> {
+            let mut schedule = self.schedules.get(&beneficiary).ok_or(Error::ScheduleNotExist)?;
+
+            let amount = self._vested_amount(&schedule) - schedule.released;
+            if amount == 0 {
+                return Err(Error::ClaimedAll);
+            }
+
+            schedule.released += amount;
+            self.schedules.insert(&beneficiary, &schedule);
+            self.total_allocated -= amount;
+            self._transfer(beneficiary, amount)?;
+            self.env().emit_event(Released { beneficiary, amount });
+
+            Ok(amount)
+        }
+
+        /// Stop the schedule of `beneficiary`. What is vested stays releasable, the rest goes back to the owner
+        #[ink(message)]
+        #[modifiers(only_owner)]
+        pub fn revoke(&mut self, beneficiary: AccountId) -> Result<(), Error> {
+            let refund = self._revoke_schedule(&beneficiary)?;
+            if refund > 0 {
+                self._transfer(self.owner(), refund)?;
+            }
+            self.env().emit_event(Revoked { beneficiary, refund });
+
+            Ok(())
+        }
+
+        #[ink(message)]
+        pub fn get_schedule(&self, beneficiary: AccountId) -> Option<VestingSchedule> {
+            self.schedules.get(&beneficiary)
+        }
+
+        #[ink(message)]
+        pub fn get_token(&self) -> AccountId {
+            self.token
+        }
+
+        #[ink(message)]
+        pub fn get_total_allocated(&self) -> Balance {
+            self.total_allocated
+        }
+
+        /// Tokens of the schedule vested at the current block timestamp
+        fn _vested_amount(&self, schedule: &VestingSchedule) -> Balance {
+            let now = self.env().block_timestamp();
+
+            if schedule.revoked || now >= schedule.start.saturating_add(schedule.duration) {
+                return schedule.total;
+            }
+            if now < schedule.start.saturating_add(schedule.cliff) {
+                return 0;
+            }
+
+            let elapsed = (now - schedule.start) as u128;
+            mul_div(schedule.total, elapsed, schedule.duration as u128).unwrap_or(schedule.total)
+        }
+
+        /// Cut the schedule of `beneficiary` down to what is vested now and return the unvested rest
+        fn _revoke_schedule(&mut self, beneficiary: &AccountId) -> Result<Balance, Error> {
+            let mut schedule = self.schedules.get(beneficiary).ok_or(Error::ScheduleNotExist)?;
+            if !schedule.revocable || schedule.revoked {
+                return Err(Error::NotRevocable);
+            }
+
+            let vested = self._vested_amount(&schedule);
+            let refund = schedule.total - vested;
+            schedule.total = vested;
+            schedule.revoked = true;
+            self.schedules.insert(beneficiary, &schedule);
+            self.total_allocated -= refund;
+
+            Ok(refund)
+        }
+
+        fn _transfer(&self, to: AccountId, amount: Balance) -> Result<(), Error> {
+            let result = Psp22Ref::transfer_builder(&self.token, to, amount, Vec::<u8>::new()).try_invoke();
+            if !matches!(result, Ok(Ok(Ok(_)))) {
+                return Err(Error::CannotTransfer);
+            }
+            Ok(())
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        /// 4_000 tokens vesting from 1_000 to 5_000 with a cliff at 2_000. Schedules are written directly,
+        /// `add_schedule` queries the token balance with a cross-contract call
+        fn setup(revocable: bool) -> (Vesting, AccountId) {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+
+            let mut vesting = Vesting::new(accounts.django);
+            let schedule = VestingSchedule {
+                start: 1_000,
+                cliff: 1_000,
+                duration: 4_000,
+                total: 4_000,
+                revocable,
+                ..Default::default()
+            };
+            vesting.schedules.insert(&accounts.bob, &schedule);
+            vesting.total_allocated = schedule.total;
+
+            (vesting, accounts.bob)
+        }
+
+        #[ink::test]
+        fn nothing_is_releasable_before_the_cliff() {
+            let (vesting, beneficiary) = setup(false);
+
+            test::set_block_timestamp::<DefaultEnvironment>(500);
+            assert_eq!(vesting.releasable(beneficiary), 0);
+            test::set_block_timestamp::<DefaultEnvironment>(1_999);
+            assert_eq!(vesting.releasable(beneficiary), 0);
+        }
+
+        #[ink::test]
+        fn tokens_vest_linearly_from_the_cliff() {
+            let (vesting, beneficiary) = setup(false);
+
+            test::set_block_timestamp::<DefaultEnvironment>(2_000);
+            assert_eq!(vesting.releasable(beneficiary), 1_000);
+            test::set_block_timestamp::<DefaultEnvironment>(3_000);
+            assert_eq!(vesting.releasable(beneficiary), 2_000);
+        }
+
+        #[ink::test]
+        fn everything_is_releasable_after_the_end() {
+            let (vesting, beneficiary) = setup(false);
+
+            test::set_block_timestamp::<DefaultEnvironment>(5_000);
+            assert_eq!(vesting.releasable(beneficiary), 4_000);
+            test::set_block_timestamp::<DefaultEnvironment>(50_000);
+            assert_eq!(vesting.releasable(beneficiary), 4_000);
+        }
+
+        #[ink::test]
+        fn revoke_keeps_the_vested_part() {
+            let (mut vesting, beneficiary) = setup(true);
+
+            test::set_block_timestamp::<DefaultEnvironment>(3_000);
+            assert_eq!(vesting._revoke_schedule(&beneficiary), Ok(2_000));
+            assert_eq!(vesting.get_total_allocated(), 2_000);
+            assert_eq!(vesting._revoke_schedule(&beneficiary), Err(Error::NotRevocable));
+
+            // Vesting stops at the revocation
+            test::set_block_timestamp::<DefaultEnvironment>(5_000);
+            assert_eq!(vesting.releasable(beneficiary), 2_000);
+        }
+
+        #[ink::test]
+        fn irrevocable_schedules_can_not_be_revoked() {
+            let (mut vesting, beneficiary) = setup(false);
+
+            assert_eq!(vesting._revoke_schedule(&beneficiary), Err(Error::NotRevocable));
+            assert_eq!(vesting.get_total_allocated(), 4_000);
+        }
+    }
+}
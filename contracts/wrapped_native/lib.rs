@@ -0,0 +1,238 @@
+#![cfg_attr(not(feature = "std"), no_std)]
+#![feature(min_specialization)]
+
+/// PSP22 representation of the native currency, every token is backed 1:1 by native currency held by the contract
+#[openbrush::contract]
+pub mod wrapped_native {
+    use ink::codegen::{EmitEvent, Env};
+    use logics_pkg::impls::psp34_artzero_support::admin::*;
+    use logics_pkg::traits::{admin::*, error::Error, roles::*};
+    use openbrush::{
+        contracts::access_control::*,
+        contracts::ownable::*,
+        contracts::psp22::extensions::metadata::*,
+        modifiers,
+        traits::{Storage, String},
+    };
+
+    #[ink(storage)]
+    #[derive(Default, Storage)]
+    pub struct WrappedNative {
+        #[storage_field]
+        psp22: psp22::Data,
+        #[storage_field]
+        metadata: metadata::Data,
+        #[storage_field]
+        ownable: ownable::Data,
+        #[storage_field]
+        access_control: access_control::Data,
+        #[storage_field]
+        admin_data: admin::data::Data,
+    }
+
+    /// Event emitted when a token transfer occurs.
+    #[ink(event)]
+    pub struct Transfer {
+        #[ink(topic)]
+        from: Option<AccountId>,
+        #[ink(topic)]
+        to: Option<AccountId>,
+        value: Balance,
+    }
+
+    /// Event emitted when an approval occurs that `spender` is allowed to withdraw
+    /// up to the amount of `value` tokens from `owner`.
+    #[ink(event)]
+    pub struct Approval {
+        #[ink(topic)]
+        owner: AccountId,
+        #[ink(topic)]
+        spender: AccountId,
+        value: Balance,
+    }
+
+    /// Event emitted when native currency is wrapped.
+    #[ink(event)]
+    pub struct Deposit {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    /// Event emitted when tokens are unwrapped back to native currency.
+    #[ink(event)]
+    pub struct Withdrawal {
+        #[ink(topic)]
+        account: AccountId,
+        amount: Balance,
+    }
+
+    impl PSP22 for WrappedNative {}
+    impl PSP22Metadata for WrappedNative {}
+    impl Ownable for WrappedNative {}
+    impl AccessControl for WrappedNative {}
+
+    impl AdminTrait for WrappedNative {
+        /// Only the native currency above the total supply and the existential deposit can be withdrawn,
+        /// deposits always stay redeemable
+        #[ink(message)]
+        #[modifiers(only_role(TREASURER))]
+        fn withdraw_fee(&mut self, value: Balance, receiver: AccountId) -> Result<(), Error> {
+            let excess = self
+                .env()
+                .balance()
+                .saturating_sub(self.env().minimum_balance())
+                .saturating_sub(self.total_supply());
+            if value > excess {
+                return Err(Error::NotEnoughBalance);
+            }
+            if self.env().transfer(receiver, value).is_err() {
+                return Err(Error::WithdrawFeeError);
+            }
+            Ok(())
+        }
+    }
+
+    impl WrappedNative {
+        #[ink(constructor)]
+        pub fn new(name: Option<String>, symbol: Option<String>, decimal: u8) -> Self {
+            let mut instance = Self::default();
+
+            instance._init_with_owner(instance.env().caller());
+            instance._init_with_admin(instance.env().caller());
+            instance._setup_role(TREASURER, instance.env().caller());
+            instance.metadata.name = name;
+            instance.metadata.symbol = symbol;
+            instance.metadata.decimals = decimal;
+            instance
+        }
+
+        /// Mint as many tokens to the caller as native currency was transferred
+        #[ink(message, payable)]
+        pub fn deposit(&mut self) -> Result<(), PSP22Error> {
+            let account = self.env().caller();
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(PSP22Error::Custom(String::from("W::ZeroAmount")));
+            }
+
+            self._mint_to(account, amount)?;
+            self.env().emit_event(Deposit { account, amount });
+
+            Ok(())
+        }
+
+        /// Burn `amount` tokens of the caller and send the same amount of native currency back
+        #[ink(message)]
+        pub fn withdraw(&mut self, amount: Balance) -> Result<(), PSP22Error> {
+            let account = self.env().caller();
+            if amount == 0 {
+                return Err(PSP22Error::Custom(String::from("W::ZeroAmount")));
+            }
+
+            self._burn_from(account, amount)?;
+            self.env()
+                .transfer(account, amount)
+                .map_err(|_| PSP22Error::Custom(String::from("W::TransferFailed")))?;
+            self.env().emit_event(Withdrawal { account, amount });
+
+            Ok(())
+        }
+    }
+
+    // Override event emission methods
+    impl psp22::Internal for WrappedNative {
+        fn _emit_transfer_event(&self, from: Option<AccountId>, to: Option<AccountId>, value: Balance) {
+            self.env().emit_event(Transfer { from, to, value });
+        }
+
+        fn _emit_approval_event(&self, owner: AccountId, spender: AccountId, value: Balance) {
+            self.env().emit_event(Approval { owner, spender, value });
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+        use ink::env::{test, DefaultEnvironment};
+
+        fn native_balance(account: AccountId) -> Balance {
+            test::get_account_balance::<DefaultEnvironment>(account).unwrap_or(0)
+        }
+
+        /// Alice deploys the contract at Django's address, it only holds the existential deposit
+        fn setup() -> (WrappedNative, test::DefaultAccounts<DefaultEnvironment>) {
+            let accounts = test::default_accounts::<DefaultEnvironment>();
+            test::set_callee::<DefaultEnvironment>(accounts.django);
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            test::set_account_balance::<DefaultEnvironment>(
+                accounts.django,
+                ink::env::minimum_balance::<DefaultEnvironment>(),
+            );
+
+            (WrappedNative::new(None, None, 18), accounts)
+        }
+
+        /// Native currency sent to the contract, the off-chain environment does not move it by itself
+        fn send_native(contract: AccountId, amount: Balance) {
+            test::set_account_balance::<DefaultEnvironment>(contract, native_balance(contract) + amount);
+        }
+
+        fn deposit(wrapped: &mut WrappedNative, contract: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+            send_native(contract, amount);
+            test::set_value_transferred::<DefaultEnvironment>(amount);
+            let result = wrapped.deposit();
+            test::set_value_transferred::<DefaultEnvironment>(0);
+            result
+        }
+
+        #[ink::test]
+        fn deposit_and_withdraw_are_one_to_one() {
+            let (mut wrapped, accounts) = setup();
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(deposit(&mut wrapped, accounts.django, 1_000).is_ok());
+            assert_eq!(wrapped.balance_of(accounts.bob), 1_000);
+            assert_eq!(wrapped.total_supply(), 1_000);
+            assert_eq!(
+                deposit(&mut wrapped, accounts.django, 0),
+                Err(PSP22Error::Custom(String::from("W::ZeroAmount")))
+            );
+
+            let bob_balance = native_balance(accounts.bob);
+            assert!(wrapped.withdraw(400).is_ok());
+            assert_eq!(wrapped.balance_of(accounts.bob), 600);
+            assert_eq!(wrapped.total_supply(), 600);
+            assert_eq!(native_balance(accounts.bob), bob_balance + 400);
+            assert_eq!(wrapped.withdraw(601), Err(PSP22Error::InsufficientBalance));
+            assert_eq!(native_balance(accounts.bob), bob_balance + 400);
+        }
+
+        #[ink::test]
+        fn withdraw_fee_only_takes_the_excess() {
+            let (mut wrapped, accounts) = setup();
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(deposit(&mut wrapped, accounts.django, 1_000).is_ok());
+            // Sent without a deposit, no token is backed by it
+            send_native(accounts.django, 500);
+
+            assert_eq!(
+                wrapped.withdraw_fee(500, accounts.bob),
+                Err(Error::AccessControlError(AccessControlError::MissingRole))
+            );
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(wrapped.withdraw_fee(501, accounts.alice), Err(Error::NotEnoughBalance));
+            assert!(wrapped.withdraw_fee(500, accounts.alice).is_ok());
+            assert_eq!(wrapped.withdraw_fee(1, accounts.alice), Err(Error::NotEnoughBalance));
+
+            // The last holder can still redeem everything
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert!(wrapped.withdraw(1_000).is_ok());
+            assert_eq!(
+                native_balance(accounts.django),
+                ink::env::minimum_balance::<DefaultEnvironment>()
+            );
+        }
+    }
+}
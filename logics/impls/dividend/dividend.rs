@@ -0,0 +1,283 @@
+use crate::impls::math::mul_div;
+use crate::traits::admin::Psp22Ref;
+pub use crate::traits::dividend::*;
+use crate::traits::roles::FEE_MANAGER;
+use ink::prelude::vec::Vec;
+use openbrush::contracts::access_control::{self, only_role};
+use openbrush::contracts::ownable::*;
+use openbrush::contracts::psp22::{self, PSP22Error};
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, DefaultEnv, Storage, String};
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+/// Scale of `magnified_per_share`, keeps the rounding error of small distributions to big supplies low
+pub const MAGNITUDE: u128 = 1 << 64;
+/// Maximum number of accounts excluded from dividends, the eligible supply computation iterates over them
+pub const MAX_EXCLUDED: usize = 16;
+
+/// Holder dividend bookkeeping.
+///
+/// `magnified_per_share` is the total amount of dividends paid per token since the deployment, multiplied by
+/// `MAGNITUDE`. Every account keeps a snapshot of it from its last balance change, dividends earned since then
+/// are `balance * (magnified_per_share - snapshot) / MAGNITUDE`. They are moved to `pending` before each balance
+/// change, so distributions never iterate over holders. Fees redistributed by reflection change balances
+/// without a transfer, so reflection must stay disabled on tokens paying dividends.
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    pub reward_token: Option<AccountId>,
+    pub magnified_per_share: u128,
+    pub snapshots: Mapping<AccountId, u128>,
+    pub pending: Mapping<AccountId, Balance>,
+    pub withdrawn: Mapping<AccountId, Balance>,
+    pub excluded: Vec<AccountId>,
+    pub total_distributed: Balance,
+}
+
+impl<T> Dividend for T
+where
+    T: Storage<Data> + Storage<psp22::Data> + Storage<ownable::Data> + Storage<access_control::Data> + psp22::Internal,
+{
+    #[openbrush::modifiers(only_owner)]
+    default fn distribute_native(&mut self) -> Result<(), PSP22Error> {
+        if self.data::<Data>().reward_token.is_some() {
+            return Err(error("D::WrongRewardToken"));
+        }
+
+        let amount = Self::env().transferred_value();
+        self._distribute_dividends(amount)
+    }
+
+    #[openbrush::modifiers(only_owner)]
+    default fn distribute_psp22(&mut self, amount: Balance) -> Result<(), PSP22Error> {
+        let token = self
+            .data::<Data>()
+            .reward_token
+            .ok_or_else(|| error("D::WrongRewardToken"))?;
+        let caller = Self::env().caller();
+        let contract = Self::env().account_id();
+
+        // Fee on transfer tokens deliver less than `amount`, only the received part is distributed
+        let balance_before = Psp22Ref::balance_of(&token, contract);
+        let result = Psp22Ref::transfer_from_builder(&token, caller, contract, amount, Vec::<u8>::new()).try_invoke();
+        if !matches!(result, Ok(Ok(Ok(_)))) {
+            return Err(error("D::TransferFailed"));
+        }
+        let received = Psp22Ref::balance_of(&token, contract).saturating_sub(balance_before);
+
+        self._distribute_dividends(received)
+    }
+
+    default fn withdraw_dividend(&mut self) -> Result<Balance, PSP22Error> {
+        let caller = Self::env().caller();
+        self._update_dividends(&caller)?;
+
+        let amount = self.data::<Data>().pending.get(&caller).unwrap_or(0);
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        let data = self.data::<Data>();
+        data.pending.remove(&caller);
+        let withdrawn = data.withdrawn.get(&caller).unwrap_or(0).saturating_add(amount);
+        data.withdrawn.insert(&caller, &withdrawn);
+
+        // The state is updated before the transfer, a reentrant call finds nothing left to withdraw
+        match data.reward_token {
+            None => Self::env()
+                .transfer(caller, amount)
+                .map_err(|_| error("D::TransferFailed"))?,
+            Some(token) => {
+                let result = Psp22Ref::transfer_builder(&token, caller, amount, Vec::<u8>::new()).try_invoke();
+                if !matches!(result, Ok(Ok(Ok(_)))) {
+                    return Err(error("D::TransferFailed"));
+                }
+            }
+        }
+        self._emit_dividend_withdrawn_event(caller, amount);
+
+        Ok(amount)
+    }
+
+    default fn withdrawable_dividend_of(&self, account: AccountId) -> Balance {
+        self._accrued_dividend_of(&account).unwrap_or(0)
+    }
+
+    default fn withdrawn_dividend_of(&self, account: AccountId) -> Balance {
+        self.data::<Data>().withdrawn.get(&account).unwrap_or(0)
+    }
+
+    default fn total_dividends_distributed(&self) -> Balance {
+        self.data::<Data>().total_distributed
+    }
+
+    #[openbrush::modifiers(only_owner)]
+    default fn set_reward_token(&mut self, token: Option<AccountId>) -> Result<(), PSP22Error> {
+        if self.data::<Data>().total_distributed > 0 {
+            return Err(error("D::AlreadyDistributed"));
+        }
+        if token == Some(Self::env().account_id()) {
+            return Err(error("D::WrongRewardToken"));
+        }
+
+        self.data::<Data>().reward_token = token;
+        Ok(())
+    }
+
+    default fn get_reward_token(&self) -> Option<AccountId> {
+        self.data::<Data>().reward_token
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    default fn exclude_from_dividends(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        self._exclude_from_dividends(account)
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    default fn include_in_dividends(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        let index = self
+            .data::<Data>()
+            .excluded
+            .iter()
+            .position(|excluded| *excluded == account)
+            .ok_or_else(|| error("D::NotExcluded"))?;
+
+        let data = self.data::<Data>();
+        data.excluded.swap_remove(index);
+        let per_share = data.magnified_per_share;
+        data.snapshots.insert(&account, &per_share);
+
+        Ok(())
+    }
+
+    default fn is_excluded_from_dividends(&self, account: AccountId) -> bool {
+        self._is_excluded_from_dividends(&account)
+    }
+}
+
+pub trait DividendInternal {
+    /// User must override this method in their contract.
+    fn _emit_dividends_distributed_event(&self, _from: AccountId, _amount: Balance);
+
+    /// User must override this method in their contract.
+    fn _emit_dividend_withdrawn_event(&self, _to: AccountId, _amount: Balance);
+
+    fn _is_excluded_from_dividends(&self, account: &AccountId) -> bool;
+
+    /// Balances earning dividends, the total supply without the excluded accounts
+    fn _eligible_supply(&self) -> Balance;
+
+    /// Pending dividends of `account` plus the ones earned since its last balance change
+    fn _accrued_dividend_of(&self, account: &AccountId) -> Result<Balance, PSP22Error>;
+
+    /// Move the dividends earned by `account` so far to `pending`. Has to be called before each balance change
+    fn _update_dividends(&mut self, account: &AccountId) -> Result<(), PSP22Error>;
+
+    /// Settle the dividends of both sides, to be used in `psp22::Internal::_before_token_transfer`
+    fn _before_dividend_transfer(&mut self, from: Option<&AccountId>, to: Option<&AccountId>)
+        -> Result<(), PSP22Error>;
+
+    /// Share `amount` of the reward token already held by the contract between the eligible holders
+    fn _distribute_dividends(&mut self, amount: Balance) -> Result<(), PSP22Error>;
+
+    fn _exclude_from_dividends(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+}
+
+fn error(code: &str) -> PSP22Error {
+    PSP22Error::Custom(String::from(code))
+}
+
+impl<T> DividendInternal for T
+where
+    T: Storage<Data> + Storage<psp22::Data> + psp22::Internal,
+{
+    default fn _emit_dividends_distributed_event(&self, _from: AccountId, _amount: Balance) {}
+
+    default fn _emit_dividend_withdrawn_event(&self, _to: AccountId, _amount: Balance) {}
+
+    default fn _is_excluded_from_dividends(&self, account: &AccountId) -> bool {
+        self.data::<Data>().excluded.contains(account)
+    }
+
+    default fn _eligible_supply(&self) -> Balance {
+        let excluded = self.data::<Data>().excluded.iter().fold(0, |total: Balance, account| {
+            total.saturating_add(self._balance_of(account))
+        });
+
+        self.data::<psp22::Data>().supply.saturating_sub(excluded)
+    }
+
+    default fn _accrued_dividend_of(&self, account: &AccountId) -> Result<Balance, PSP22Error> {
+        let data = self.data::<Data>();
+        let pending = data.pending.get(account).unwrap_or(0);
+        if self._is_excluded_from_dividends(account) {
+            return Ok(pending);
+        }
+
+        let per_share = data.magnified_per_share - data.snapshots.get(account).unwrap_or(0);
+        let earned = mul_div(self._balance_of(account), per_share, MAGNITUDE).ok_or_else(|| error("D::Overflow"))?;
+
+        pending.checked_add(earned).ok_or_else(|| error("D::Overflow"))
+    }
+
+    default fn _update_dividends(&mut self, account: &AccountId) -> Result<(), PSP22Error> {
+        let accrued = self._accrued_dividend_of(account)?;
+
+        let data = self.data::<Data>();
+        let per_share = data.magnified_per_share;
+        data.pending.insert(account, &accrued);
+        data.snapshots.insert(account, &per_share);
+
+        Ok(())
+    }
+
+    default fn _before_dividend_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+    ) -> Result<(), PSP22Error> {
+        for account in [from, to].into_iter().flatten() {
+            self._update_dividends(account)?;
+        }
+
+        Ok(())
+    }
+
+    default fn _distribute_dividends(&mut self, amount: Balance) -> Result<(), PSP22Error> {
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let supply = self._eligible_supply();
+        if supply == 0 {
+            return Err(error("D::NoHolders"));
+        }
+
+        let increase = mul_div(amount, MAGNITUDE, supply).ok_or_else(|| error("D::Overflow"))?;
+        let data = self.data::<Data>();
+        data.magnified_per_share = data
+            .magnified_per_share
+            .checked_add(increase)
+            .ok_or_else(|| error("D::Overflow"))?;
+        data.total_distributed = data.total_distributed.saturating_add(amount);
+        self._emit_dividends_distributed_event(Self::env().caller(), amount);
+
+        Ok(())
+    }
+
+    default fn _exclude_from_dividends(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        if self._is_excluded_from_dividends(&account) {
+            return Err(error("D::AlreadyExcluded"));
+        }
+        if self.data::<Data>().excluded.len() >= MAX_EXCLUDED {
+            return Err(error("D::TooManyExcluded"));
+        }
+
+        // Keep what was earned before the exclusion
+        self._update_dividends(&account)?;
+        self.data::<Data>().excluded.push(account);
+
+        Ok(())
+    }
+}
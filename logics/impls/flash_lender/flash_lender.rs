@@ -0,0 +1,125 @@
+pub use crate::traits::flash_lender::*;
+use crate::traits::roles::FEE_MANAGER;
+use ink::env::CallFlags;
+use ink::prelude::vec::Vec;
+use openbrush::contracts::access_control::{self, only_role};
+use openbrush::contracts::psp22::{self, PSP22Error};
+use openbrush::traits::{AccountId, Balance, DefaultEnv, Flush, Storage, String};
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+pub const FLASH_FEE_DENOMINATOR: u128 = 10_000;
+/// 1% in basis points
+pub const MAX_FLASH_FEE: u128 = 100;
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    /// Flash loan fee in basis points
+    pub fee: u128,
+    /// Set for the duration of a flash loan, a borrower can not take a second loan from its callback
+    pub in_flash_loan: bool,
+}
+
+impl<T> FlashLender for T
+where
+    T: Storage<Data> + Storage<psp22::Data> + Storage<access_control::Data> + psp22::Internal + Flush,
+{
+    default fn max_flash_loan(&self, token: AccountId) -> Balance {
+        if token != Self::env().account_id() {
+            return 0;
+        }
+
+        self._max_supply().saturating_sub(self.data::<psp22::Data>().supply)
+    }
+
+    default fn flash_fee(&self, token: AccountId, amount: Balance) -> Result<Balance, PSP22Error> {
+        if token != Self::env().account_id() {
+            return Err(error("FL::WrongToken"));
+        }
+
+        amount
+            .checked_mul(self.data::<Data>().fee)
+            .map(|fee| fee / FLASH_FEE_DENOMINATOR)
+            .ok_or_else(|| error("FL::Overflow"))
+    }
+
+    default fn flash_loan(
+        &mut self,
+        receiver: AccountId,
+        token: AccountId,
+        amount: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error> {
+        if self.data::<Data>().in_flash_loan {
+            return Err(error("FL::Reentrancy"));
+        }
+        if amount > self.max_flash_loan(token) {
+            return Err(error("FL::AmountTooHigh"));
+        }
+
+        let fee = self.flash_fee(token, amount)?;
+        let repayment = amount.checked_add(fee).ok_or_else(|| error("FL::Overflow"))?;
+        let initiator = Self::env().caller();
+        let lender = Self::env().account_id();
+
+        self._mint_to(receiver, amount)?;
+        self.data::<Data>().in_flash_loan = true;
+        // The borrower calls back into the token, so the state has to be
+        // written before the call and reloaded after it
+        self.flush();
+        let result = FlashBorrowerRef::on_flash_loan_builder(&receiver, initiator, token, amount, fee, data)
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .try_invoke();
+        self.load();
+        self.data::<Data>().in_flash_loan = false;
+
+        if !matches!(result, Ok(Ok(Ok(_)))) {
+            return Err(error("FL::CallbackFailed"));
+        }
+
+        let allowance = self._allowance(&receiver, &lender);
+        if allowance < repayment {
+            return Err(error("FL::RepayFailed"));
+        }
+        self._approve_from_to(receiver, lender, allowance - repayment)?;
+        self._burn_from(receiver, repayment)?;
+        self._emit_flash_loan_event(receiver, amount, fee);
+
+        Ok(())
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    default fn set_flash_fee(&mut self, fee: u128) -> Result<(), PSP22Error> {
+        if fee > MAX_FLASH_FEE {
+            return Err(error("FL::FeeTooHigh"));
+        }
+
+        self.data::<Data>().fee = fee;
+        Ok(())
+    }
+}
+
+pub trait FlashLenderInternal {
+    /// User must override this method in their contract.
+    fn _emit_flash_loan_event(&self, _receiver: AccountId, _amount: Balance, _fee: Balance);
+
+    /// Highest total supply the token may reach, loans are minted on top of the supply.
+    /// Capped tokens must override it with their cap
+    fn _max_supply(&self) -> Balance;
+}
+
+fn error(code: &str) -> PSP22Error {
+    PSP22Error::Custom(String::from(code))
+}
+
+impl<T> FlashLenderInternal for T
+where
+    T: Storage<Data>,
+{
+    default fn _emit_flash_loan_event(&self, _receiver: AccountId, _amount: Balance, _fee: Balance) {}
+
+    default fn _max_supply(&self) -> Balance {
+        Balance::MAX
+    }
+}
@@ -0,0 +1,88 @@
+/// `a * b / denominator` rounded down, computed with a 256 bit intermediate product.
+/// Returns `None` if `denominator` is zero or the result does not fit into `u128`
+pub fn mul_div(a: u128, b: u128, denominator: u128) -> Option<u128> {
+    if denominator == 0 {
+        return None;
+    }
+
+    let (high, low) = full_mul(a, b);
+    if high >= denominator {
+        return None;
+    }
+
+    // Binary long division of the 256 bit product, the remainder always stays below `denominator`
+    let mut remainder = high;
+    let mut quotient: u128 = 0;
+    for bit in (0..128).rev() {
+        let carry = remainder >> 127;
+        remainder = (remainder << 1) | ((low >> bit) & 1);
+        quotient <<= 1;
+        if carry == 1 || remainder >= denominator {
+            remainder = remainder.wrapping_sub(denominator);
+            quotient |= 1;
+        }
+    }
+
+    Some(quotient)
+}
+
+/// 256 bit product of `a` and `b` as `(high, low)` halves
+fn full_mul(a: u128, b: u128) -> (u128, u128) {
+    const MASK: u128 = u64::MAX as u128;
+
+    let (a_high, a_low) = (a >> 64, a & MASK);
+    let (b_high, b_low) = (b >> 64, b & MASK);
+
+    let low_low = a_low * b_low;
+    let high_low = a_high * b_low;
+    let low_high = a_low * b_high;
+    let high_high = a_high * b_high;
+
+    let cross = (low_low >> 64) + (high_low & MASK) + (low_high & MASK);
+    let low = (cross << 64) | (low_low & MASK);
+    let high = high_high + (high_low >> 64) + (low_high >> 64) + (cross >> 64);
+
+    (high, low)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_mul_splits_the_product() {
+        assert_eq!(full_mul(0, u128::MAX), (0, 0));
+        assert_eq!(
+            full_mul(u64::MAX as u128, u64::MAX as u128),
+            (0, (u64::MAX as u128) * (u64::MAX as u128))
+        );
+        assert_eq!(full_mul(1 << 64, 1 << 64), (1, 0));
+        assert_eq!(full_mul(u128::MAX, 2), (1, u128::MAX - 1));
+        assert_eq!(full_mul(u128::MAX, u128::MAX), (u128::MAX - 1, 1));
+    }
+
+    #[test]
+    fn mul_div_matches_plain_arithmetic() {
+        assert_eq!(mul_div(7, 3, 2), Some(10));
+        assert_eq!(mul_div(0, u128::MAX, 1), Some(0));
+        assert_eq!(mul_div(1_000, 1 << 64, 3), Some(1_000 * (1 << 64) / 3));
+    }
+
+    #[test]
+    fn mul_div_handles_products_above_u128() {
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX), Some(u128::MAX));
+        assert_eq!(mul_div(u128::MAX, 2, 2), Some(u128::MAX));
+        assert_eq!(mul_div(1 << 64, 1 << 64, 2), Some(1 << 127));
+        assert_eq!(mul_div(u128::MAX, 3, 4), Some(3 * (1 << 126) - 1));
+        // Denominator above 2^127, the remainder overflows during the long division
+        assert_eq!(mul_div(u128::MAX, u128::MAX - 1, u128::MAX), Some(u128::MAX - 1));
+    }
+
+    #[test]
+    fn mul_div_rejects_overflow_and_zero_denominator() {
+        assert_eq!(mul_div(1, 1, 0), None);
+        assert_eq!(mul_div(u128::MAX, 2, 1), None);
+        assert_eq!(mul_div(1 << 64, 1 << 64, 1), None);
+        assert_eq!(mul_div(u128::MAX, u128::MAX, u128::MAX - 1), None);
+    }
+}
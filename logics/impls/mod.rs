@@ -1,2 +1,9 @@
+pub mod dividend;
+pub mod flash_lender;
+pub mod math;
+pub mod pause_manager;
 pub mod psp22_fee;
+pub mod psp22_permit;
+pub mod psp22_votes;
 pub mod psp34_artzero_support;
+pub mod staking;
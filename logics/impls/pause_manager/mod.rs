@@ -0,0 +1 @@
+pub mod pause_manager;
@@ -0,0 +1,23 @@
+use crate::traits::error::Error;
+pub use crate::traits::pause_manager::*;
+use crate::traits::roles::PAUSER;
+use openbrush::{
+    contracts::{access_control::*, pausable::*},
+    modifiers,
+    traits::Storage,
+};
+
+impl<T> PauseManager for T
+where
+    T: Storage<pausable::Data> + Storage<access_control::Data>,
+{
+    #[modifiers(only_role(PAUSER))]
+    default fn pause(&mut self) -> Result<(), Error> {
+        self._pause()
+    }
+
+    #[modifiers(only_role(PAUSER))]
+    default fn unpause(&mut self) -> Result<(), Error> {
+        self._unpause()
+    }
+}
@@ -1 +1,2 @@
 pub mod psp22_fee;
+pub mod reflection;
@@ -1,52 +1,757 @@
-use crate::traits::psp22_fee::Psp22Fee;
+use crate::traits::error::Psp22FeeError;
+use crate::traits::psp22_fee::{FeeRecipient, Limit, Psp22Fee};
+use crate::traits::roles::FEE_MANAGER;
+use crate::traits::router::*;
+use ink::env::CallFlags;
+use ink::prelude::vec::Vec;
+use openbrush::contracts::access_control::{self, only_role};
 use openbrush::contracts::ownable::*;
-use openbrush::contracts::psp22::{self, PSP22Error};
-use openbrush::traits::{Balance, Storage};
+use openbrush::contracts::psp22;
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, BlockNumber, DefaultEnv, Flush, Storage, Timestamp, ZERO_ADDRESS};
 
 pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
 
+/// Fees and percentage limits are expressed in basis points
+pub const FEE_DENOMINATOR: u128 = 10_000;
+/// Highest fee the owner is allowed to set, 25%
+pub const MAX_FEE: u128 = 2_500;
+/// Maximum number of fee destinations, bounds the work done on each transfer
+pub const MAX_FEE_RECIPIENTS: usize = 8;
+/// Highest fee which can be charged during the launch window, 99%
+pub const MAX_LAUNCH_FEE: u128 = 9_900;
+/// Longest transfer cooldown the owner is allowed to set, one hour in milliseconds
+pub const MAX_COOLDOWN: Timestamp = 3_600_000;
+
 #[derive(Default, Debug)]
 #[openbrush::upgradeable_storage(STORAGE_KEY)]
 pub struct Data {
-    pub max_wallet: Balance,
-    pub max_tx: Balance,
+    pub max_wallet: Limit,
+    pub max_tx: Limit,
     pub fee: u128,
+    pub buy_fee: u128,
+    pub sell_fee: u128,
+    pub pairs: Mapping<AccountId, bool>,
+    pub fee_exempt: Mapping<AccountId, bool>,
+    pub limit_exempt: Mapping<AccountId, bool>,
+    pub fee_recipients: Vec<(FeeRecipient, u128)>,
+    pub router: Option<AccountId>,
+    pub swap_threshold: Balance,
+    pub accumulated_liquidity: Balance,
+    pub swapping: bool,
+    pub trading_enabled: bool,
+    pub launch_block: BlockNumber,
+    pub launch_blocks: BlockNumber,
+    pub launch_fee: u128,
+    pub launch_max_tx: Limit,
+    pub cooldown: Timestamp,
+    pub last_transfer: Mapping<AccountId, Timestamp>,
+    pub blacklist: Mapping<AccountId, bool>,
 }
 
 impl<T> Psp22Fee for T
 where
-    T: Storage<Data> + Storage<psp22::Data> + Storage<ownable::Data>,
+    T: Storage<Data> + Storage<psp22::Data> + Storage<ownable::Data> + Storage<access_control::Data> + Flush,
 {
-    #[openbrush::modifiers(only_owner)]
-    fn set_max_wallet(&mut self, max_wallet: u128) -> Result<(), PSP22Error> {
-        self.data::<Data>().max_wallet = (self.data::<psp22::Data>().supply * max_wallet) / 100;
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_max_wallet(&mut self, max_wallet: u128) -> Result<(), Psp22FeeError> {
+        self._set_max_wallet(Limit::Percentage(max_wallet))
+    }
 
-        Ok(())
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_max_wallet_amount(&mut self, max_wallet: Balance) -> Result<(), Psp22FeeError> {
+        self._set_max_wallet(Limit::Absolute(max_wallet))
     }
 
-    #[openbrush::modifiers(only_owner)]
-    fn set_max_tx(&mut self, max_tx: u128) -> Result<(), PSP22Error> {
-        self.data::<Data>().max_tx = (self.data::<psp22::Data>().supply * max_tx) / 100;
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_max_tx(&mut self, max_tx: u128) -> Result<(), Psp22FeeError> {
+        self._set_max_tx(Limit::Percentage(max_tx))
+    }
 
-        Ok(())
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_max_tx_amount(&mut self, max_tx: Balance) -> Result<(), Psp22FeeError> {
+        self._set_max_tx(Limit::Absolute(max_tx))
     }
 
     fn get_max_wallet(&mut self) -> Balance {
-        self.data::<Data>().max_wallet
+        let limit = self.data::<Data>().max_wallet;
+        self._effective_limit(limit)
     }
 
     fn get_max_tx(&mut self) -> Balance {
-        self.data::<Data>().max_tx
+        let limit = self.data::<Data>().max_tx;
+        self._effective_limit(limit)
     }
 
-    #[openbrush::modifiers(only_owner)]
-    fn set_fee(&mut self, fee: u128) -> Result<(), PSP22Error> {
-        self.data::<Data>().fee = fee;
+    fn get_max_wallet_policy(&self) -> Limit {
+        self.data::<Data>().max_wallet
+    }
 
-        Ok(())
+    fn get_max_tx_policy(&self) -> Limit {
+        self.data::<Data>().max_tx
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_fee(&mut self, fee: u128) -> Result<(), Psp22FeeError> {
+        self._set_fee(fee)
     }
 
     fn get_fee(&mut self) -> u128 {
         self.data::<Data>().fee
     }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_buy_fee(&mut self, fee: u128) -> Result<(), Psp22FeeError> {
+        self._check_fee(fee)?;
+        self.data::<Data>().buy_fee = fee;
+        self._emit_fee_updated();
+
+        Ok(())
+    }
+
+    fn get_buy_fee(&self) -> u128 {
+        self.data::<Data>().buy_fee
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_sell_fee(&mut self, fee: u128) -> Result<(), Psp22FeeError> {
+        self._check_fee(fee)?;
+        self.data::<Data>().sell_fee = fee;
+        self._emit_fee_updated();
+
+        Ok(())
+    }
+
+    fn get_sell_fee(&self) -> u128 {
+        self.data::<Data>().sell_fee
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn add_pair(&mut self, pair: AccountId) -> Result<(), Psp22FeeError> {
+        self.data::<Data>().pairs.insert(&pair, &true);
+
+        Ok(())
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn remove_pair(&mut self, pair: AccountId) -> Result<(), Psp22FeeError> {
+        self.data::<Data>().pairs.remove(&pair);
+
+        Ok(())
+    }
+
+    fn is_pair(&self, account: AccountId) -> bool {
+        self.data::<Data>().pairs.get(&account).unwrap_or(false)
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn add_fee_exempt(&mut self, account: AccountId) -> Result<(), Psp22FeeError> {
+        self.data::<Data>().fee_exempt.insert(&account, &true);
+
+        Ok(())
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn remove_fee_exempt(&mut self, account: AccountId) -> Result<(), Psp22FeeError> {
+        self.data::<Data>().fee_exempt.remove(&account);
+
+        Ok(())
+    }
+
+    fn is_fee_exempt(&self, account: AccountId) -> bool {
+        self.data::<Data>().fee_exempt.get(&account).unwrap_or(false)
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn add_limit_exempt(&mut self, account: AccountId) -> Result<(), Psp22FeeError> {
+        self.data::<Data>().limit_exempt.insert(&account, &true);
+
+        Ok(())
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn remove_limit_exempt(&mut self, account: AccountId) -> Result<(), Psp22FeeError> {
+        self.data::<Data>().limit_exempt.remove(&account);
+
+        Ok(())
+    }
+
+    fn is_limit_exempt(&self, account: AccountId) -> bool {
+        self.data::<Data>().limit_exempt.get(&account).unwrap_or(false)
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_fee_recipients(&mut self, recipients: Vec<(FeeRecipient, u128)>) -> Result<(), Psp22FeeError> {
+        self._set_fee_recipients(recipients)
+    }
+
+    fn get_fee_recipients(&self) -> Vec<(FeeRecipient, u128)> {
+        self.data::<Data>().fee_recipients.clone()
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_router(&mut self, router: AccountId) -> Result<(), Psp22FeeError> {
+        self.data::<Data>().router = Some(router);
+
+        Ok(())
+    }
+
+    fn get_router(&self) -> Option<AccountId> {
+        self.data::<Data>().router
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_swap_threshold(&mut self, threshold: Balance) -> Result<(), Psp22FeeError> {
+        self.data::<Data>().swap_threshold = threshold;
+
+        Ok(())
+    }
+
+    fn get_swap_threshold(&self) -> Balance {
+        self.data::<Data>().swap_threshold
+    }
+
+    fn get_accumulated_liquidity(&self) -> Balance {
+        self.data::<Data>().accumulated_liquidity
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn swap_and_liquify(&mut self) -> Result<(), Psp22FeeError> {
+        self._swap_and_liquify()
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn enable_trading(&mut self) -> Result<(), Psp22FeeError> {
+        if self.data::<Data>().trading_enabled {
+            return Err(Psp22FeeError::TradingAlreadyEnabled);
+        }
+
+        let block = Self::env().block_number();
+        let data = self.data::<Data>();
+        data.trading_enabled = true;
+        data.launch_block = block;
+        self._emit_trading_enabled_event(block);
+
+        Ok(())
+    }
+
+    fn is_trading_enabled(&self) -> bool {
+        self.data::<Data>().trading_enabled
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_launch_protection(&mut self, blocks: BlockNumber, fee: u128, max_tx: u128) -> Result<(), Psp22FeeError> {
+        if self.data::<Data>().trading_enabled {
+            return Err(Psp22FeeError::TradingAlreadyEnabled);
+        }
+        if fee > MAX_LAUNCH_FEE {
+            return Err(Psp22FeeError::FeeTooHigh);
+        }
+        self._check_limit(Limit::Percentage(max_tx))?;
+
+        let data = self.data::<Data>();
+        data.launch_blocks = blocks;
+        data.launch_fee = fee;
+        data.launch_max_tx = Limit::Percentage(max_tx);
+
+        Ok(())
+    }
+
+    fn get_launch_protection(&self) -> (BlockNumber, u128, Limit) {
+        let data = self.data::<Data>();
+        (data.launch_blocks, data.launch_fee, data.launch_max_tx)
+    }
+
+    fn is_launch_window(&self) -> bool {
+        self._in_launch_window()
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn set_cooldown(&mut self, cooldown: Timestamp) -> Result<(), Psp22FeeError> {
+        if cooldown > MAX_COOLDOWN {
+            return Err(Psp22FeeError::InvalidCooldown);
+        }
+        self.data::<Data>().cooldown = cooldown;
+
+        Ok(())
+    }
+
+    fn get_cooldown(&self) -> Timestamp {
+        self.data::<Data>().cooldown
+    }
+
+    fn next_allowed_transfer(&self, account: AccountId) -> Timestamp {
+        let data = self.data::<Data>();
+
+        match data.last_transfer.get(&account) {
+            Some(last_transfer) if data.cooldown > 0 => last_transfer.saturating_add(data.cooldown),
+            _ => 0,
+        }
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn add_to_blacklist(&mut self, accounts: Vec<AccountId>) -> Result<(), Psp22FeeError> {
+        for account in accounts.iter() {
+            self.data::<Data>().blacklist.insert(account, &true);
+            self._emit_blacklist_updated_event(*account, true);
+        }
+
+        Ok(())
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn remove_from_blacklist(&mut self, accounts: Vec<AccountId>) -> Result<(), Psp22FeeError> {
+        for account in accounts.iter() {
+            self.data::<Data>().blacklist.remove(account);
+            self._emit_blacklist_updated_event(*account, false);
+        }
+
+        Ok(())
+    }
+
+    fn is_blacklisted(&self, account: AccountId) -> bool {
+        self.data::<Data>().blacklist.get(&account).unwrap_or(false)
+    }
+}
+
+pub trait Psp22FeeInternal {
+    /// User must override this method in their contract.
+    fn _emit_fee_charged_event(&self, _from: AccountId, _to: AccountId, _fee: Balance);
+
+    /// User must override this method in their contract.
+    fn _emit_fee_updated_event(&self, _fee: u128, _buy_fee: u128, _sell_fee: u128);
+
+    /// User must override this method in their contract.
+    fn _emit_max_tx_updated_event(&self, _max_tx: Limit);
+
+    /// User must override this method in their contract.
+    fn _emit_max_wallet_updated_event(&self, _max_wallet: Limit);
+
+    /// User must override this method in their contract.
+    fn _emit_trading_enabled_event(&self, _block: BlockNumber);
+
+    /// User must override this method in their contract.
+    fn _emit_blacklist_updated_event(&self, _account: AccountId, _blacklisted: bool);
+
+    /// Emit `FeeUpdated` with the current transfer, buy and sell fees
+    fn _emit_fee_updated(&self);
+
+    /// Moves `value` from `from` to `to` applying the fee and limit policy.
+    /// Every transfer path of the fee token (`transfer`, `transfer_from`, ...) must go through this hook
+    fn _transfer_with_fee(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), Psp22FeeError>;
+
+    /// Blacklist, trading and limit checks of a transfer of `value` from `from` to `to`
+    fn _check_transfer(&self, from: &AccountId, to: &AccountId, value: Balance) -> Result<(), Psp22FeeError>;
+
+    /// Takes the fee from `value`, distributes it and moves the rest from `from` to `to`.
+    /// The checks and the cooldown are left to the caller, see `_transfer_with_fee`
+    fn _charge_fee_and_transfer(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), Psp22FeeError>;
+
+    /// Fails if any of `accounts` is blacklisted
+    fn _check_blacklist(&self, accounts: &[AccountId]) -> Result<(), Psp22FeeError>;
+
+    /// Returns true if the account is exempt from the fee or from the limits
+    fn _is_exempt(&self, account: &AccountId) -> bool;
+
+    /// Returns true during the first `launch_blocks` blocks after trading was enabled
+    fn _in_launch_window(&self) -> bool;
+
+    /// Before trading is enabled only transfers from or to exempt accounts are allowed
+    fn _check_trading(&self, from: &AccountId, to: &AccountId) -> Result<(), Psp22FeeError>;
+
+    /// Enforce the transfer cooldown on the sender, or on the buyer when tokens come from a pair,
+    /// and record the time of the transfer
+    fn _apply_cooldown(&mut self, from: &AccountId, to: &AccountId) -> Result<(), Psp22FeeError>;
+
+    /// Check `max_tx` and `max_wallet` limits for a transfer of `value` from `from` to `to`
+    fn _check_limits(&self, from: &AccountId, to: &AccountId, value: Balance) -> Result<(), Psp22FeeError>;
+
+    /// Amount of tokens allowed by `limit` for the current total supply
+    fn _effective_limit(&self, limit: Limit) -> Balance;
+
+    /// Fails if a percentage limit is zero or above 100%
+    fn _check_limit(&self, limit: Limit) -> Result<(), Psp22FeeError>;
+
+    fn _set_max_wallet(&mut self, limit: Limit) -> Result<(), Psp22FeeError>;
+
+    fn _set_max_tx(&mut self, limit: Limit) -> Result<(), Psp22FeeError>;
+
+    /// Returns the part of `value` which is taken as a fee for a transfer from `from` to `to`
+    fn _compute_fee(&self, from: &AccountId, to: &AccountId, value: Balance) -> Result<Balance, Psp22FeeError>;
+
+    /// Fee rate in basis points which applies to a transfer from `from` to `to`.
+    /// Transfers from a registered pair are buys, transfers to a registered pair are sells
+    fn _fee_rate(&self, from: &AccountId, to: &AccountId) -> u128;
+
+    /// Splits `tax` taken from `from` between the fee recipients according to their weights.
+    /// Without configured recipients the whole fee goes to the owner
+    fn _distribute_fee(&mut self, from: AccountId, tax: Balance, data: Vec<u8>) -> Result<(), Psp22FeeError>;
+
+    /// Returns true if `FeeRecipient::Reflect` may be configured.
+    /// Tokens keeping their balances in the reflection module must override it
+    fn _reflection_enabled(&self) -> bool;
+
+    /// Redistributes `amount` taken from `from` to all holders, fails unless the token overrides it
+    fn _reflect_share(&mut self, from: AccountId, amount: Balance) -> Result<(), Psp22FeeError>;
+
+    /// Validate and store the fee recipients, the weights must sum up to `FEE_DENOMINATOR`
+    fn _set_fee_recipients(&mut self, recipients: Vec<(FeeRecipient, u128)>) -> Result<(), Psp22FeeError>;
+
+    /// Returns true if the accumulated liquidity fee should be added to the pool during a transfer from `from`.
+    /// Buys are skipped because the pair is in the middle of a swap
+    fn _should_swap(&self, from: &AccountId) -> bool;
+
+    /// Approve the router for the accumulated liquidity fee and add it to the pool, LP tokens go to the owner
+    fn _swap_and_liquify(&mut self) -> Result<(), Psp22FeeError>;
+
+    /// Fails if `fee` is above `MAX_FEE`
+    fn _check_fee(&self, fee: u128) -> Result<(), Psp22FeeError>;
+
+    /// Set the wallet to wallet fee in basis points, fails if it is above `MAX_FEE`
+    fn _set_fee(&mut self, fee: u128) -> Result<(), Psp22FeeError>;
+}
+
+impl<T> Psp22FeeInternal for T
+where
+    T: Storage<Data> + Storage<psp22::Data> + Storage<ownable::Data> + psp22::Internal + Flush,
+{
+    default fn _emit_fee_charged_event(&self, _from: AccountId, _to: AccountId, _fee: Balance) {}
+
+    default fn _emit_fee_updated_event(&self, _fee: u128, _buy_fee: u128, _sell_fee: u128) {}
+
+    default fn _emit_max_tx_updated_event(&self, _max_tx: Limit) {}
+
+    default fn _emit_max_wallet_updated_event(&self, _max_wallet: Limit) {}
+
+    default fn _emit_trading_enabled_event(&self, _block: BlockNumber) {}
+
+    default fn _emit_blacklist_updated_event(&self, _account: AccountId, _blacklisted: bool) {}
+
+    default fn _emit_fee_updated(&self) {
+        let data = self.data::<Data>();
+        self._emit_fee_updated_event(data.fee, data.buy_fee, data.sell_fee);
+    }
+
+    default fn _transfer_with_fee(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), Psp22FeeError> {
+        self._check_transfer(&from, &to, value)?;
+        self._apply_cooldown(&from, &to)?;
+        self._charge_fee_and_transfer(from, to, value, data)
+    }
+
+    default fn _check_transfer(&self, from: &AccountId, to: &AccountId, value: Balance) -> Result<(), Psp22FeeError> {
+        self._check_blacklist(&[*from, *to])?;
+        self._check_trading(from, to)?;
+        self._check_limits(from, to, value)
+    }
+
+    default fn _charge_fee_and_transfer(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        value: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), Psp22FeeError> {
+        if self._should_swap(&from) {
+            // A failed swap must not block the transfer, the fee stays accumulated for the next try
+            let _ = self._swap_and_liquify();
+        }
+
+        let tax = self._compute_fee(&from, &to, value)?;
+        let amount = value.checked_sub(tax).ok_or(Psp22FeeError::Overflow)?;
+
+        if tax > 0 {
+            self._distribute_fee(from, tax, data.clone())?;
+            self._emit_fee_charged_event(from, to, tax);
+        }
+        self._transfer_from_to(from, to, amount, data)?;
+
+        Ok(())
+    }
+
+    default fn _check_blacklist(&self, accounts: &[AccountId]) -> Result<(), Psp22FeeError> {
+        if accounts.iter().any(|account| self.is_blacklisted(*account)) {
+            return Err(Psp22FeeError::Blacklisted);
+        }
+
+        Ok(())
+    }
+
+    default fn _is_exempt(&self, account: &AccountId) -> bool {
+        self.is_fee_exempt(*account) || self.is_limit_exempt(*account)
+    }
+
+    default fn _in_launch_window(&self) -> bool {
+        let data = self.data::<Data>();
+
+        data.trading_enabled && Self::env().block_number() < data.launch_block.saturating_add(data.launch_blocks)
+    }
+
+    default fn _check_trading(&self, from: &AccountId, to: &AccountId) -> Result<(), Psp22FeeError> {
+        if !self.data::<Data>().trading_enabled && !self._is_exempt(from) && !self._is_exempt(to) {
+            return Err(Psp22FeeError::TradingDisabled);
+        }
+
+        Ok(())
+    }
+
+    default fn _apply_cooldown(&mut self, from: &AccountId, to: &AccountId) -> Result<(), Psp22FeeError> {
+        if self.data::<Data>().cooldown == 0 {
+            return Ok(());
+        }
+
+        let account = if self.is_pair(*from) { to } else { from };
+        if self._is_exempt(account) || self.is_pair(*account) {
+            return Ok(());
+        }
+
+        let now = Self::env().block_timestamp();
+        if now < self.next_allowed_transfer(*account) {
+            return Err(Psp22FeeError::CooldownActive);
+        }
+        self.data::<Data>().last_transfer.insert(account, &now);
+
+        Ok(())
+    }
+
+    default fn _check_limits(&self, from: &AccountId, to: &AccountId, value: Balance) -> Result<(), Psp22FeeError> {
+        let to_exempt = self.is_limit_exempt(*to);
+        let mut max_tx = self._effective_limit(self.data::<Data>().max_tx);
+        if self._in_launch_window() {
+            max_tx = max_tx.min(self._effective_limit(self.data::<Data>().launch_max_tx));
+        }
+        let max_wallet = self._effective_limit(self.data::<Data>().max_wallet);
+
+        if !to_exempt && !self.is_limit_exempt(*from) && value > max_tx {
+            return Err(Psp22FeeError::MaxTxExceeded);
+        }
+
+        let balance_after = value.checked_add(self._balance_of(to)).unwrap_or(Balance::MAX);
+        if !to_exempt && balance_after > max_wallet {
+            return Err(Psp22FeeError::MaxWalletExceeded);
+        }
+
+        Ok(())
+    }
+
+    default fn _effective_limit(&self, limit: Limit) -> Balance {
+        match limit {
+            Limit::Percentage(share) => {
+                let supply = self.data::<psp22::Data>().supply;
+                supply
+                    .checked_mul(share)
+                    .map(|limit| limit / FEE_DENOMINATOR)
+                    .unwrap_or_else(|| supply / FEE_DENOMINATOR * share)
+            }
+            Limit::Absolute(amount) => amount,
+        }
+    }
+
+    default fn _check_limit(&self, limit: Limit) -> Result<(), Psp22FeeError> {
+        if let Limit::Percentage(share) = limit {
+            if share == 0 || share > FEE_DENOMINATOR {
+                return Err(Psp22FeeError::InvalidLimit);
+            }
+        }
+
+        Ok(())
+    }
+
+    default fn _set_max_wallet(&mut self, limit: Limit) -> Result<(), Psp22FeeError> {
+        self._check_limit(limit)?;
+        self.data::<Data>().max_wallet = limit;
+        self._emit_max_wallet_updated_event(limit);
+
+        Ok(())
+    }
+
+    default fn _set_max_tx(&mut self, limit: Limit) -> Result<(), Psp22FeeError> {
+        self._check_limit(limit)?;
+        self.data::<Data>().max_tx = limit;
+        self._emit_max_tx_updated_event(limit);
+
+        Ok(())
+    }
+
+    default fn _compute_fee(&self, from: &AccountId, to: &AccountId, value: Balance) -> Result<Balance, Psp22FeeError> {
+        let is_tax = *to != ZERO_ADDRESS.into()
+            && *from != ZERO_ADDRESS.into()
+            && !self.is_fee_exempt(*from)
+            && !self.is_fee_exempt(*to);
+
+        if !is_tax {
+            return Ok(0);
+        }
+
+        value
+            .checked_mul(self._fee_rate(from, to))
+            .map(|fee| fee / FEE_DENOMINATOR)
+            .ok_or(Psp22FeeError::Overflow)
+    }
+
+    default fn _fee_rate(&self, from: &AccountId, to: &AccountId) -> u128 {
+        if self._in_launch_window() {
+            self.data::<Data>().launch_fee
+        } else if self.is_pair(*from) {
+            self.data::<Data>().buy_fee
+        } else if self.is_pair(*to) {
+            self.data::<Data>().sell_fee
+        } else {
+            self.data::<Data>().fee
+        }
+    }
+
+    default fn _distribute_fee(&mut self, from: AccountId, tax: Balance, data: Vec<u8>) -> Result<(), Psp22FeeError> {
+        let recipients = self.data::<Data>().fee_recipients.clone();
+
+        if recipients.is_empty() {
+            let owner = self.data::<ownable::Data>().owner;
+            self._transfer_from_to(from, owner, tax, data)?;
+            return Ok(());
+        }
+
+        let mut remaining = tax;
+        for (index, (recipient, weight)) in recipients.iter().enumerate() {
+            // The last recipient takes the rounding dust
+            let share = if index + 1 == recipients.len() {
+                remaining
+            } else {
+                tax.checked_mul(*weight)
+                    .map(|share| share / FEE_DENOMINATOR)
+                    .ok_or(Psp22FeeError::Overflow)?
+            };
+            remaining -= share;
+
+            if share == 0 {
+                continue;
+            }
+
+            match recipient {
+                FeeRecipient::Account(account) => self._transfer_from_to(from, *account, share, data.clone())?,
+                FeeRecipient::Burn => self._burn_from(from, share)?,
+                FeeRecipient::Reflect => self._reflect_share(from, share)?,
+                FeeRecipient::Liquidity => {
+                    let contract = Self::env().account_id();
+                    self._transfer_from_to(from, contract, share, data.clone())?;
+                    let fee_data = self.data::<Data>();
+                    fee_data.accumulated_liquidity = fee_data.accumulated_liquidity.saturating_add(share);
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    default fn _reflection_enabled(&self) -> bool {
+        false
+    }
+
+    default fn _reflect_share(&mut self, _from: AccountId, _amount: Balance) -> Result<(), Psp22FeeError> {
+        Err(Psp22FeeError::InvalidFeeRecipients)
+    }
+
+    default fn _set_fee_recipients(&mut self, recipients: Vec<(FeeRecipient, u128)>) -> Result<(), Psp22FeeError> {
+        if recipients.is_empty() || recipients.len() > MAX_FEE_RECIPIENTS {
+            return Err(Psp22FeeError::InvalidFeeRecipients);
+        }
+
+        let mut total_weight: u128 = 0;
+        let reflection_enabled = self._reflection_enabled();
+        for (recipient, weight) in recipients.iter() {
+            if *weight == 0 || (*recipient == FeeRecipient::Reflect && !reflection_enabled) {
+                return Err(Psp22FeeError::InvalidFeeRecipients);
+            }
+            // Shares sent to the zero address would neither be burned nor spendable
+            if *recipient == FeeRecipient::Account(ZERO_ADDRESS.into()) {
+                return Err(Psp22FeeError::InvalidFeeRecipients);
+            }
+            total_weight = total_weight.saturating_add(*weight);
+        }
+
+        if total_weight != FEE_DENOMINATOR {
+            return Err(Psp22FeeError::InvalidFeeRecipients);
+        }
+
+        self.data::<Data>().fee_recipients = recipients;
+
+        Ok(())
+    }
+
+    default fn _should_swap(&self, from: &AccountId) -> bool {
+        let data = self.data::<Data>();
+
+        !data.swapping
+            && data.router.is_some()
+            && data.swap_threshold > 0
+            && data.accumulated_liquidity >= data.swap_threshold
+            && !self.is_pair(*from)
+    }
+
+    default fn _swap_and_liquify(&mut self) -> Result<(), Psp22FeeError> {
+        let router = self.data::<Data>().router.ok_or(Psp22FeeError::RouterNotSet)?;
+        let amount = self.data::<Data>().accumulated_liquidity;
+
+        if amount == 0 {
+            return Ok(());
+        }
+
+        let contract = Self::env().account_id();
+        let lp_receiver = self.data::<ownable::Data>().owner;
+
+        let balance_before = self._balance_of(&contract);
+
+        self._approve_from_to(contract, router, amount)?;
+        self.data::<Data>().swapping = true;
+        // The router pulls the tokens with `transfer_from`, so the state has to be
+        // written before the call and reloaded after it
+        self.flush();
+        let result = LiquidityRouterRef::swap_and_add_liquidity_builder(&router, contract, amount, lp_receiver)
+            .call_flags(CallFlags::default().set_allow_reentry(true))
+            .try_invoke();
+        self.load();
+        self.data::<Data>().swapping = false;
+        self._approve_from_to(contract, router, 0)?;
+
+        let spent = balance_before.saturating_sub(self._balance_of(&contract));
+        let data = self.data::<Data>();
+        data.accumulated_liquidity = data.accumulated_liquidity.saturating_sub(spent);
+
+        match result {
+            Ok(Ok(Ok(_))) => Ok(()),
+            _ => Err(Psp22FeeError::SwapFailed),
+        }
+    }
+
+    default fn _check_fee(&self, fee: u128) -> Result<(), Psp22FeeError> {
+        if fee > MAX_FEE {
+            return Err(Psp22FeeError::FeeTooHigh);
+        }
+
+        Ok(())
+    }
+
+    default fn _set_fee(&mut self, fee: u128) -> Result<(), Psp22FeeError> {
+        self._check_fee(fee)?;
+        self.data::<Data>().fee = fee;
+        self._emit_fee_updated();
+
+        Ok(())
+    }
 }
@@ -0,0 +1,315 @@
+use crate::traits::psp22_reflection::Psp22Reflection;
+use crate::traits::roles::FEE_MANAGER;
+use ink::prelude::vec::Vec;
+use openbrush::contracts::access_control::{self, only_role};
+use openbrush::contracts::psp22::{self, PSP22Error};
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, Storage, String, ZERO_ADDRESS};
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+/// Reflected units per token when the supply is empty
+pub const INITIAL_RATE: Balance = 1_000_000_000;
+/// Maximum number of accounts excluded from reflection, the rate computation iterates over them
+pub const MAX_EXCLUDED: usize = 16;
+
+/// Holder redistribution bookkeeping.
+///
+/// When enabled, balances of included accounts are kept in reflected units in `r_owned` and the
+/// token balance is `r_owned / rate`. Reflecting a fee shrinks `r_total`, which lowers the rate and
+/// raises every included balance without iterating over holders. Excluded accounts keep their token
+/// balance in `psp22::Data::balances` as usual.
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    pub enabled: bool,
+    pub r_total: Balance,
+    pub r_owned: Mapping<AccountId, Balance>,
+    pub excluded: Vec<AccountId>,
+    pub total_fees: Balance,
+}
+
+impl<T> Psp22Reflection for T
+where
+    T: Storage<Data> + Storage<psp22::Data> + Storage<access_control::Data>,
+{
+    fn is_reflection_enabled(&self) -> bool {
+        self.data::<Data>().enabled
+    }
+
+    fn total_reflected_fees(&self) -> Balance {
+        self.data::<Data>().total_fees
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn exclude_from_reflection(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        if !self.data::<Data>().enabled {
+            return Err(PSP22Error::Custom(String::from("R::Disabled")));
+        }
+        if self._is_excluded_from_reflection(&account) {
+            return Err(PSP22Error::Custom(String::from("R::AlreadyExcluded")));
+        }
+        if self.data::<Data>().excluded.len() >= MAX_EXCLUDED {
+            return Err(PSP22Error::Custom(String::from("R::TooManyExcluded")));
+        }
+
+        let balance = self._reflection_balance_of(&account);
+        self.data::<psp22::Data>().balances.insert(&account, &balance);
+        self.data::<Data>().excluded.push(account);
+
+        Ok(())
+    }
+
+    #[openbrush::modifiers(only_role(FEE_MANAGER))]
+    fn include_in_reflection(&mut self, account: AccountId) -> Result<(), PSP22Error> {
+        let index = self
+            .data::<Data>()
+            .excluded
+            .iter()
+            .position(|excluded| *excluded == account)
+            .ok_or_else(|| PSP22Error::Custom(String::from("R::NotExcluded")))?;
+
+        // Convert the token balance back to reflected units at the current rate,
+        // so the account keeps exactly its balance and the rate does not move
+        let balance = self.data::<psp22::Data>().balances.get(&account).unwrap_or(0);
+        let rate = self._reflection_rate();
+        self.data::<Data>().excluded.swap_remove(index);
+        let r_owned = balance.checked_mul(rate).ok_or_else(overflow)?;
+        let old_r_owned = self.data::<Data>().r_owned.get(&account).unwrap_or(0);
+
+        let data = self.data::<Data>();
+        data.r_total = data
+            .r_total
+            .checked_sub(old_r_owned)
+            .and_then(|r_total| r_total.checked_add(r_owned))
+            .ok_or_else(overflow)?;
+        data.r_owned.insert(&account, &r_owned);
+        self.data::<psp22::Data>().balances.remove(&account);
+
+        Ok(())
+    }
+
+    fn is_excluded_from_reflection(&self, account: AccountId) -> bool {
+        self._is_excluded_from_reflection(&account)
+    }
+}
+
+pub trait Psp22ReflectionInternal {
+    /// Turn on reflection bookkeeping. Has to be called in the constructor before anything is minted
+    fn _init_reflection(&mut self);
+
+    fn _is_excluded_from_reflection(&self, account: &AccountId) -> bool;
+
+    /// Current amount of reflected units per token
+    fn _reflection_rate(&self) -> Balance;
+
+    /// Token balance of `owner`, to be used as `psp22::Internal::_balance_of`
+    fn _reflection_balance_of(&self, owner: &AccountId) -> Balance;
+
+    /// Reflection aware `psp22::Internal::_transfer_from_to`
+    fn _reflection_transfer_from_to(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        amount: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Reflection aware `psp22::Internal::_mint_to`
+    fn _reflection_mint_to(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error>;
+
+    /// Reflection aware `psp22::Internal::_burn_from`
+    fn _reflection_burn_from(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error>;
+
+    /// Take `amount` from `from` and redistribute it to all included holders
+    fn _reflect_fee(&mut self, from: AccountId, amount: Balance) -> Result<(), PSP22Error>;
+
+    fn _increase_balance(&mut self, account: &AccountId, amount: Balance, rate: Balance) -> Result<(), PSP22Error>;
+
+    fn _decrease_balance(&mut self, account: &AccountId, amount: Balance, rate: Balance) -> Result<(), PSP22Error>;
+}
+
+fn overflow() -> PSP22Error {
+    PSP22Error::Custom(String::from("R::Overflow"))
+}
+
+impl<T> Psp22ReflectionInternal for T
+where
+    T: Storage<Data> + Storage<psp22::Data> + psp22::Internal,
+{
+    default fn _init_reflection(&mut self) {
+        self.data::<Data>().enabled = true;
+    }
+
+    default fn _is_excluded_from_reflection(&self, account: &AccountId) -> bool {
+        self.data::<Data>().excluded.contains(account)
+    }
+
+    default fn _reflection_rate(&self) -> Balance {
+        let data = self.data::<Data>();
+        let supply = self.data::<psp22::Data>().supply;
+
+        if supply == 0 || data.r_total == 0 {
+            return INITIAL_RATE;
+        }
+
+        let mut r_supply = data.r_total;
+        let mut t_supply = supply;
+        for account in data.excluded.iter() {
+            let r_owned = data.r_owned.get(account).unwrap_or(0);
+            let t_owned = self.data::<psp22::Data>().balances.get(account).unwrap_or(0);
+            if r_owned > r_supply || t_owned > t_supply {
+                return data.r_total / supply;
+            }
+            r_supply -= r_owned;
+            t_supply -= t_owned;
+        }
+
+        if t_supply == 0 || r_supply < data.r_total / supply {
+            return data.r_total / supply;
+        }
+
+        r_supply / t_supply
+    }
+
+    default fn _reflection_balance_of(&self, owner: &AccountId) -> Balance {
+        if !self.data::<Data>().enabled || self._is_excluded_from_reflection(owner) {
+            return self.data::<psp22::Data>().balances.get(owner).unwrap_or(0);
+        }
+
+        self.data::<Data>().r_owned.get(owner).unwrap_or(0) / self._reflection_rate()
+    }
+
+    default fn _reflection_transfer_from_to(
+        &mut self,
+        from: AccountId,
+        to: AccountId,
+        amount: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error> {
+        if from == ZERO_ADDRESS.into() {
+            return Err(PSP22Error::ZeroSenderAddress);
+        }
+        if to == ZERO_ADDRESS.into() {
+            return Err(PSP22Error::ZeroRecipientAddress);
+        }
+
+        self._before_token_transfer(Some(&from), Some(&to), &amount)?;
+        let rate = self._reflection_rate();
+        self._decrease_balance(&from, amount, rate)?;
+        self._do_safe_transfer_check(&from, &to, &amount, &data)?;
+        self._increase_balance(&to, amount, rate)?;
+        self._after_token_transfer(Some(&from), Some(&to), &amount)?;
+        self._emit_transfer_event(Some(from), Some(to), amount);
+
+        Ok(())
+    }
+
+    default fn _reflection_mint_to(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+        if account == ZERO_ADDRESS.into() {
+            return Err(PSP22Error::ZeroRecipientAddress);
+        }
+
+        self._before_token_transfer(None, Some(&account), &amount)?;
+        let rate = self._reflection_rate();
+        self._increase_balance(&account, amount, rate)?;
+        if self.data::<Data>().enabled {
+            let r_amount = amount.checked_mul(rate).ok_or_else(overflow)?;
+            let data = self.data::<Data>();
+            data.r_total = data.r_total.checked_add(r_amount).ok_or_else(overflow)?;
+        }
+        let token = self.data::<psp22::Data>();
+        token.supply = token.supply.checked_add(amount).ok_or_else(overflow)?;
+        self._after_token_transfer(None, Some(&account), &amount)?;
+        self._emit_transfer_event(None, Some(account), amount);
+
+        Ok(())
+    }
+
+    default fn _reflection_burn_from(&mut self, account: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+        if account == ZERO_ADDRESS.into() {
+            return Err(PSP22Error::ZeroSenderAddress);
+        }
+
+        self._before_token_transfer(Some(&account), None, &amount)?;
+        let rate = self._reflection_rate();
+        self._decrease_balance(&account, amount, rate)?;
+        if self.data::<Data>().enabled {
+            let data = self.data::<Data>();
+            data.r_total = data.r_total.saturating_sub(amount.saturating_mul(rate));
+        }
+        let token = self.data::<psp22::Data>();
+        token.supply = token.supply.saturating_sub(amount);
+        self._after_token_transfer(Some(&account), None, &amount)?;
+        self._emit_transfer_event(Some(account), None, amount);
+
+        Ok(())
+    }
+
+    default fn _reflect_fee(&mut self, from: AccountId, amount: Balance) -> Result<(), PSP22Error> {
+        if !self.data::<Data>().enabled {
+            return Err(PSP22Error::Custom(String::from("R::Disabled")));
+        }
+
+        let rate = self._reflection_rate();
+        self._decrease_balance(&from, amount, rate)?;
+
+        let r_amount = amount.checked_mul(rate).ok_or_else(overflow)?;
+        let data = self.data::<Data>();
+        data.r_total = data.r_total.saturating_sub(r_amount);
+        data.total_fees = data.total_fees.saturating_add(amount);
+
+        Ok(())
+    }
+
+    default fn _increase_balance(
+        &mut self,
+        account: &AccountId,
+        amount: Balance,
+        rate: Balance,
+    ) -> Result<(), PSP22Error> {
+        let enabled = self.data::<Data>().enabled;
+
+        if !enabled || self._is_excluded_from_reflection(account) {
+            let balances = &mut self.data::<psp22::Data>().balances;
+            let balance = balances.get(account).unwrap_or(0);
+            balances.insert(account, &balance.checked_add(amount).ok_or_else(overflow)?);
+        }
+
+        if enabled {
+            let r_amount = amount.checked_mul(rate).ok_or_else(overflow)?;
+            let r_owned = &mut self.data::<Data>().r_owned;
+            let r_balance = r_owned.get(account).unwrap_or(0);
+            r_owned.insert(account, &r_balance.checked_add(r_amount).ok_or_else(overflow)?);
+        }
+
+        Ok(())
+    }
+
+    default fn _decrease_balance(
+        &mut self,
+        account: &AccountId,
+        amount: Balance,
+        rate: Balance,
+    ) -> Result<(), PSP22Error> {
+        let balance = self._reflection_balance_of(account);
+        if balance < amount {
+            return Err(PSP22Error::InsufficientBalance);
+        }
+
+        let enabled = self.data::<Data>().enabled;
+
+        if !enabled || self._is_excluded_from_reflection(account) {
+            self.data::<psp22::Data>().balances.insert(account, &(balance - amount));
+        }
+
+        if enabled {
+            let r_amount = amount.checked_mul(rate).ok_or_else(overflow)?;
+            let r_owned = &mut self.data::<Data>().r_owned;
+            let r_balance = r_owned.get(account).unwrap_or(0);
+            r_owned.insert(account, &r_balance.saturating_sub(r_amount));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1 @@
+pub mod psp22_permit;
@@ -0,0 +1,104 @@
+pub use crate::traits::psp22_permit::*;
+use ink::env::hash::{Blake2x256, HashOutput};
+use openbrush::contracts::psp22::{self, PSP22Error};
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, DefaultEnv, Storage, String, Timestamp};
+use scale::Encode;
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+/// Tag mixed into the domain separator, changing it invalidates all pending permits
+pub const PERMIT_DOMAIN: &[u8] = b"PSP22Permit";
+
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    pub nonces: Mapping<AccountId, u64>,
+}
+
+impl<T> Psp22Permit for T
+where
+    T: Storage<Data> + psp22::Internal,
+{
+    default fn permit(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        value: Balance,
+        deadline: Timestamp,
+        signature: [u8; 65],
+    ) -> Result<(), PSP22Error> {
+        if Self::env().block_timestamp() > deadline {
+            return Err(PSP22Error::Custom(String::from("PM::Expired")));
+        }
+
+        let hash = self.permit_hash(owner, spender, value, deadline);
+        if self._recover_signer(&hash, &signature)? != owner {
+            return Err(PSP22Error::Custom(String::from("PM::InvalidSignature")));
+        }
+
+        self._use_nonce(&owner)?;
+        self._approve_from_to(owner, spender, value)
+    }
+
+    default fn nonces(&self, owner: AccountId) -> u64 {
+        self.data::<Data>().nonces.get(&owner).unwrap_or(0)
+    }
+
+    default fn domain_separator(&self) -> [u8; 32] {
+        hash_encoded(&(PERMIT_DOMAIN, Self::env().account_id()))
+    }
+
+    default fn permit_hash(
+        &self,
+        owner: AccountId,
+        spender: AccountId,
+        value: Balance,
+        deadline: Timestamp,
+    ) -> [u8; 32] {
+        let nonce = self.nonces(owner);
+        hash_encoded(&(self.domain_separator(), owner, spender, value, nonce, deadline))
+    }
+}
+
+pub trait Psp22PermitInternal {
+    /// Account of the ECDSA key which signed `hash`, the blake2 hash of the compressed public key
+    fn _recover_signer(&self, hash: &[u8; 32], signature: &[u8; 65]) -> Result<AccountId, PSP22Error>;
+
+    /// Consume the current nonce of `owner`, so the signature can not be replayed
+    fn _use_nonce(&mut self, owner: &AccountId) -> Result<u64, PSP22Error>;
+}
+
+/// Blake2x256 hash of the SCALE encoded `value`
+fn hash_encoded<V: Encode>(value: &V) -> [u8; 32] {
+    let mut output = <Blake2x256 as HashOutput>::Type::default();
+    ink::env::hash_bytes::<Blake2x256>(&value.encode(), &mut output);
+    output
+}
+
+impl<T> Psp22PermitInternal for T
+where
+    T: Storage<Data>,
+{
+    default fn _recover_signer(&self, hash: &[u8; 32], signature: &[u8; 65]) -> Result<AccountId, PSP22Error> {
+        let mut public_key = [0u8; 33];
+        ink::env::ecdsa_recover(signature, hash, &mut public_key)
+            .map_err(|_| PSP22Error::Custom(String::from("PM::InvalidSignature")))?;
+
+        let mut account = <Blake2x256 as HashOutput>::Type::default();
+        ink::env::hash_bytes::<Blake2x256>(&public_key, &mut account);
+
+        Ok(AccountId::from(account))
+    }
+
+    default fn _use_nonce(&mut self, owner: &AccountId) -> Result<u64, PSP22Error> {
+        let nonces = &mut self.data::<Data>().nonces;
+        let nonce = nonces.get(owner).unwrap_or(0);
+        let next = nonce
+            .checked_add(1)
+            .ok_or_else(|| PSP22Error::Custom(String::from("PM::Overflow")))?;
+        nonces.insert(owner, &next);
+
+        Ok(nonce)
+    }
+}
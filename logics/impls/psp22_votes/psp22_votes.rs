@@ -0,0 +1,254 @@
+pub use crate::traits::psp22_votes::*;
+use openbrush::contracts::psp22::{self, PSP22Error};
+use openbrush::storage::Mapping;
+use openbrush::traits::{AccountId, Balance, BlockNumber, DefaultEnv, Storage, String};
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+/// Block number from which the checkpoint applies and the amount of votes or supply
+pub type Checkpoint = (BlockNumber, Balance);
+
+/// Voting power bookkeeping of the token.
+///
+/// Every account delegates to itself until it calls `delegate`. Checkpoints are written on mints, burns and
+/// transfers, fees sent to the treasury included. Fees redistributed by reflection do not move any token,
+/// so reflection must stay disabled on tokens with votes.
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    pub delegates: Mapping<AccountId, AccountId>,
+    pub checkpoints: Mapping<(AccountId, u32), Checkpoint>,
+    pub num_checkpoints: Mapping<AccountId, u32>,
+    pub supply_checkpoints: Mapping<u32, Checkpoint>,
+    pub num_supply_checkpoints: u32,
+}
+
+impl<T> Psp22Votes for T
+where
+    T: Storage<Data> + Storage<psp22::Data> + psp22::Internal,
+{
+    default fn get_votes(&self, account: AccountId) -> Balance {
+        self._latest_votes(&account)
+    }
+
+    default fn get_past_votes(&self, account: AccountId, block: BlockNumber) -> Result<Balance, PSP22Error> {
+        self._check_past_block(block)?;
+
+        let data = self.data::<Data>();
+        let count = data.num_checkpoints.get(&account).unwrap_or(0);
+        Ok(upper_lookup(count, block, |index| {
+            data.checkpoints.get(&(account, index))
+        }))
+    }
+
+    default fn get_past_total_supply(&self, block: BlockNumber) -> Result<Balance, PSP22Error> {
+        self._check_past_block(block)?;
+
+        let data = self.data::<Data>();
+        Ok(upper_lookup(data.num_supply_checkpoints, block, |index| {
+            data.supply_checkpoints.get(&index)
+        }))
+    }
+
+    default fn delegates(&self, account: AccountId) -> AccountId {
+        self._delegate_of(&account)
+    }
+
+    default fn delegate(&mut self, delegatee: AccountId) -> Result<(), PSP22Error> {
+        let delegator = Self::env().caller();
+        let from_delegate = self._delegate_of(&delegator);
+
+        self.data::<Data>().delegates.insert(&delegator, &delegatee);
+        self._emit_delegate_changed_event(delegator, from_delegate, delegatee);
+
+        let balance = self._balance_of(&delegator);
+        self._move_voting_power(Some(from_delegate), Some(delegatee), balance)
+    }
+
+    default fn num_checkpoints(&self, account: AccountId) -> u32 {
+        self.data::<Data>().num_checkpoints.get(&account).unwrap_or(0)
+    }
+
+    default fn checkpoints(&self, account: AccountId, index: u32) -> Option<Checkpoint> {
+        self.data::<Data>().checkpoints.get(&(account, index))
+    }
+}
+
+pub trait Psp22VotesInternal {
+    /// User must override this method in their contract.
+    fn _emit_delegate_changed_event(&self, _delegator: AccountId, _from_delegate: AccountId, _to_delegate: AccountId);
+
+    /// User must override this method in their contract.
+    fn _emit_delegate_votes_changed_event(&self, _delegate: AccountId, _previous_votes: Balance, _new_votes: Balance);
+
+    fn _delegate_of(&self, account: &AccountId) -> AccountId;
+
+    fn _latest_votes(&self, account: &AccountId) -> Balance;
+
+    /// Fails unless `block` is already mined, past lookups must not change anymore
+    fn _check_past_block(&self, block: BlockNumber) -> Result<(), PSP22Error>;
+
+    /// Update the checkpoints after a mint, burn or transfer, to be used as `psp22::Internal::_after_token_transfer`
+    fn _after_votes_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+        amount: Balance,
+    ) -> Result<(), PSP22Error>;
+
+    /// Move `amount` of votes between the delegates, `None` stands for minted or burned tokens
+    fn _move_voting_power(
+        &mut self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        amount: Balance,
+    ) -> Result<(), PSP22Error>;
+
+    /// Record `votes` for `account` at the current block
+    fn _write_checkpoint(&mut self, account: AccountId, votes: Balance);
+
+    /// Record the current total supply at the current block
+    fn _write_supply_checkpoint(&mut self);
+}
+
+/// Value of the last checkpoint written at or before `block`, checkpoints are sorted by block number
+fn upper_lookup<F>(count: u32, block: BlockNumber, checkpoint: F) -> Balance
+where
+    F: Fn(u32) -> Option<Checkpoint>,
+{
+    let mut low = 0;
+    let mut high = count;
+    while low < high {
+        let mid = low + (high - low) / 2;
+        match checkpoint(mid) {
+            Some((from_block, _)) if from_block > block => high = mid,
+            _ => low = mid + 1,
+        }
+    }
+
+    match high.checked_sub(1) {
+        Some(index) => checkpoint(index).map_or(0, |(_, value)| value),
+        None => 0,
+    }
+}
+
+impl<T> Psp22VotesInternal for T
+where
+    T: Storage<Data> + Storage<psp22::Data>,
+{
+    default fn _emit_delegate_changed_event(
+        &self,
+        _delegator: AccountId,
+        _from_delegate: AccountId,
+        _to_delegate: AccountId,
+    ) {
+    }
+
+    default fn _emit_delegate_votes_changed_event(
+        &self,
+        _delegate: AccountId,
+        _previous_votes: Balance,
+        _new_votes: Balance,
+    ) {
+    }
+
+    default fn _delegate_of(&self, account: &AccountId) -> AccountId {
+        self.data::<Data>().delegates.get(account).unwrap_or(*account)
+    }
+
+    default fn _latest_votes(&self, account: &AccountId) -> Balance {
+        let data = self.data::<Data>();
+
+        match data.num_checkpoints.get(account).unwrap_or(0).checked_sub(1) {
+            Some(index) => data.checkpoints.get(&(*account, index)).map_or(0, |(_, votes)| votes),
+            None => 0,
+        }
+    }
+
+    default fn _check_past_block(&self, block: BlockNumber) -> Result<(), PSP22Error> {
+        if block >= Self::env().block_number() {
+            return Err(PSP22Error::Custom(String::from("V::FutureLookup")));
+        }
+        Ok(())
+    }
+
+    default fn _after_votes_transfer(
+        &mut self,
+        from: Option<&AccountId>,
+        to: Option<&AccountId>,
+        amount: Balance,
+    ) -> Result<(), PSP22Error> {
+        if from.is_none() || to.is_none() {
+            self._write_supply_checkpoint();
+        }
+
+        let from = from.map(|account| self._delegate_of(account));
+        let to = to.map(|account| self._delegate_of(account));
+        self._move_voting_power(from, to, amount)
+    }
+
+    default fn _move_voting_power(
+        &mut self,
+        from: Option<AccountId>,
+        to: Option<AccountId>,
+        amount: Balance,
+    ) -> Result<(), PSP22Error> {
+        if from == to || amount == 0 {
+            return Ok(());
+        }
+
+        if let Some(delegate) = from {
+            let previous_votes = self._latest_votes(&delegate);
+            let new_votes = previous_votes
+                .checked_sub(amount)
+                .ok_or_else(|| PSP22Error::Custom(String::from("V::InsufficientVotes")))?;
+            self._write_checkpoint(delegate, new_votes);
+            self._emit_delegate_votes_changed_event(delegate, previous_votes, new_votes);
+        }
+
+        if let Some(delegate) = to {
+            let previous_votes = self._latest_votes(&delegate);
+            let new_votes = previous_votes
+                .checked_add(amount)
+                .ok_or_else(|| PSP22Error::Custom(String::from("V::Overflow")))?;
+            self._write_checkpoint(delegate, new_votes);
+            self._emit_delegate_votes_changed_event(delegate, previous_votes, new_votes);
+        }
+
+        Ok(())
+    }
+
+    default fn _write_checkpoint(&mut self, account: AccountId, votes: Balance) {
+        let block = Self::env().block_number();
+        let data = self.data::<Data>();
+        let count = data.num_checkpoints.get(&account).unwrap_or(0);
+
+        // Several updates within one block share a single checkpoint
+        match count.checked_sub(1) {
+            Some(last) if data.checkpoints.get(&(account, last)).map(|(from_block, _)| from_block) == Some(block) => {
+                data.checkpoints.insert(&(account, last), &(block, votes));
+            }
+            _ => {
+                data.checkpoints.insert(&(account, count), &(block, votes));
+                data.num_checkpoints.insert(&account, &(count + 1));
+            }
+        }
+    }
+
+    default fn _write_supply_checkpoint(&mut self) {
+        let block = Self::env().block_number();
+        let supply = self.data::<psp22::Data>().supply;
+        let data = self.data::<Data>();
+        let count = data.num_supply_checkpoints;
+
+        match count.checked_sub(1) {
+            Some(last) if data.supply_checkpoints.get(&last).map(|(from_block, _)| from_block) == Some(block) => {
+                data.supply_checkpoints.insert(&last, &(block, supply));
+            }
+            _ => {
+                data.supply_checkpoints.insert(&count, &(block, supply));
+                data.num_supply_checkpoints = count + 1;
+            }
+        }
+    }
+}
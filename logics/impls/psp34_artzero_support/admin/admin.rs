@@ -2,16 +2,17 @@ pub use crate::impls::psp34_artzero_support::admin::data;
 use crate::traits::admin::*;
 use crate::traits::error::Error;
 use crate::traits::psp34_traits::*;
+use crate::traits::roles::TREASURER;
 use ink::env::CallFlags;
 use ink::prelude::vec::Vec;
 use openbrush::{
-    contracts::{ownable::*, traits::psp34::Id},
+    contracts::{access_control::*, traits::psp34::Id},
     modifiers,
     traits::{AccountId, Balance, Storage},
 };
 
-impl<T: Storage<data::Data> + Storage<ownable::Data>> AdminTrait for T {
-    #[modifiers(only_owner)]
+impl<T: Storage<data::Data> + Storage<access_control::Data>> AdminTrait for T {
+    #[modifiers(only_role(TREASURER))]
     default fn withdraw_fee(&mut self, value: Balance, receiver: AccountId) -> Result<(), Error> {
         if value > T::env().balance() {
             return Err(Error::NotEnoughBalance);
@@ -22,7 +23,7 @@ impl<T: Storage<data::Data> + Storage<ownable::Data>> AdminTrait for T {
         Ok(())
     }
 
-    #[modifiers(only_owner)]
+    #[modifiers(only_role(TREASURER))]
     default fn tranfer_nft(
         &mut self,
         nft_contract_address: AccountId,
@@ -40,7 +41,7 @@ impl<T: Storage<data::Data> + Storage<ownable::Data>> AdminTrait for T {
         Ok(())
     }
 
-    #[modifiers(only_owner)]
+    #[modifiers(only_role(TREASURER))]
     default fn tranfer_psp22(
         &mut self,
         psp22_contract_address: AccountId,
@@ -1,11 +1,14 @@
 use crate::traits::error::Error;
+use crate::traits::roles::{METADATA_ADMIN, MINTER, TREASURER};
 pub use crate::traits::psp34_traits::*;
 use ink::prelude::{
     string::{String, ToString},
     vec::Vec,
 };
 use openbrush::{
+    contracts::access_control::{self, only_role},
     contracts::ownable::*,
+    contracts::pausable::*,
     contracts::psp34::extensions::{enumerable::*, metadata::*},
     modifier_definition, modifiers,
     storage::Mapping,
@@ -62,7 +65,9 @@ where
         + psp34::Internal
         + Storage<psp34::extensions::metadata::Data>
         + Storage<psp34::Data<psp34::extensions::enumerable::Balances>>
-        + Storage<ownable::Data>,
+        + Storage<ownable::Data>
+        + Storage<access_control::Data>
+        + Storage<pausable::Data>,
 {
     /// Get Token Count
     default fn get_last_token_id(&self) -> u64 {
@@ -70,7 +75,7 @@ where
     }
 
     /// Lock nft - Only owner token
-    #[modifiers(only_token_owner(self.owner_of(token_id.clone()).unwrap()))]
+    #[modifiers(when_not_paused, only_token_owner(self.owner_of(token_id.clone()).unwrap()))]
     default fn lock(&mut self, token_id: Id) -> Result<(), Error> {
         self.data::<Manager>().locked_token_count = self
             .data::<Manager>()
@@ -102,7 +107,7 @@ where
     }
 
     /// Change baseURI
-    #[modifiers(only_owner)]
+    #[modifiers(only_role(METADATA_ADMIN), when_not_paused)]
     default fn set_base_uri(&mut self, uri: String) -> Result<(), Error> {
         self._set_attribute(
             Id::U8(0),
@@ -112,8 +117,8 @@ where
         Ok(())
     }
 
-    /// Only Owner can set multiple attributes to a token
-    #[modifiers(only_owner)]
+    /// Only metadata admins can set multiple attributes to a token
+    #[modifiers(only_role(METADATA_ADMIN), when_not_paused)]
     default fn set_multiple_attributes(
         &mut self,
         token_id: Id,
@@ -180,7 +185,7 @@ where
     }
 
     /// Withdraws funds to contract owner
-    #[modifiers(only_owner)]
+    #[modifiers(only_role(TREASURER))]
     default fn withdraw(&mut self) -> Result<(), Error> {
         let balance = Self::env().balance();
         let current_balance = balance
@@ -194,7 +199,7 @@ where
     }
 
     /// Set max number of tokens which could be minted per call
-    #[modifiers(only_owner)]
+    #[modifiers(only_role(MINTER))]
     default fn set_max_mint_amount(&mut self, max_amount: u64) -> Result<(), Error> {
         self.data::<Manager>().max_amount = max_amount;
         Ok(())
@@ -0,0 +1,457 @@
+use crate::impls::dividend::dividend::MAGNITUDE;
+use crate::impls::math::mul_div;
+use crate::traits::admin::Psp22Ref;
+use crate::traits::error::Error;
+pub use crate::traits::staking::*;
+use ink::prelude::vec::Vec;
+use openbrush::{
+    contracts::ownable::*,
+    modifiers,
+    storage::Mapping,
+    traits::{AccountId, Balance, DefaultEnv, Storage, String, Timestamp},
+};
+
+pub const STORAGE_KEY: u32 = openbrush::storage_unique_key!(Data);
+
+/// Milliseconds per second, block timestamps are in milliseconds while the reward rate is per second
+pub const MILLIS_PER_SECOND: u128 = 1_000;
+
+/// Single pool staking bookkeeping.
+///
+/// `acc_reward_per_share` is the reward paid per staked token since the deployment, multiplied by `MAGNITUDE`.
+/// Every staker keeps a snapshot of it from its last stake change and the rewards earned before it in `pending`.
+#[derive(Default, Debug)]
+#[openbrush::upgradeable_storage(STORAGE_KEY)]
+pub struct Data {
+    pub staking_token: AccountId,
+    pub reward_token: AccountId,
+    pub reward_rate: Balance,
+    pub reward_started: bool,
+    pub last_update: Timestamp,
+    pub acc_reward_per_share: u128,
+    pub total_staked: Balance,
+    pub stakes: Mapping<AccountId, Balance>,
+    pub snapshots: Mapping<AccountId, u128>,
+    pub pending: Mapping<AccountId, Balance>,
+}
+
+impl<T> Staking for T
+where
+    T: Storage<Data> + Storage<ownable::Data>,
+{
+    default fn stake(&mut self, amount: Balance) -> Result<(), Error> {
+        if amount == 0 {
+            return Err(Error::InvalidInput);
+        }
+
+        let caller = T::env().caller();
+        self._update_pool()?;
+        self._update_staker(&caller)?;
+
+        let received = self._receive_staking_token(caller, amount)?;
+        let data = self.data::<Data>();
+        let stake = data.stakes.get(&caller).unwrap_or(0).saturating_add(received);
+        data.stakes.insert(&caller, &stake);
+        data.total_staked = data.total_staked.saturating_add(received);
+        self._emit_staked_event(caller, received);
+
+        Ok(())
+    }
+
+    default fn unstake(&mut self, amount: Balance) -> Result<(), Error> {
+        let caller = T::env().caller();
+        let stake = self.data::<Data>().stakes.get(&caller).unwrap_or(0);
+        if amount == 0 || amount > stake {
+            return Err(Error::NotEnoughBalance);
+        }
+
+        self._update_pool()?;
+        self._update_staker(&caller)?;
+
+        let data = self.data::<Data>();
+        data.stakes.insert(&caller, &(stake - amount));
+        data.total_staked -= amount;
+        let staking_token = data.staking_token;
+        self._transfer_token(staking_token, caller, amount)?;
+        self._emit_unstaked_event(caller, amount);
+
+        // The stake is always returned, rewards the pool cannot pay yet stay pending for a later claim
+        let _ = self._pay_reward(caller);
+        Ok(())
+    }
+
+    default fn claim_reward(&mut self) -> Result<Balance, Error> {
+        let caller = T::env().caller();
+        self._update_pool()?;
+        self._update_staker(&caller)?;
+
+        self._pay_reward(caller)
+    }
+
+    default fn emergency_withdraw(&mut self) -> Result<(), Error> {
+        let caller = T::env().caller();
+        let stake = self.data::<Data>().stakes.get(&caller).unwrap_or(0);
+        if stake == 0 {
+            return Err(Error::NotEnoughBalance);
+        }
+
+        // The rewards accrued so far are shared with the withdrawn stake, the caller forfeits its part
+        self._update_pool()?;
+
+        let data = self.data::<Data>();
+        data.stakes.remove(&caller);
+        data.pending.remove(&caller);
+        data.snapshots.remove(&caller);
+        data.total_staked -= stake;
+        let staking_token = data.staking_token;
+        self._transfer_token(staking_token, caller, stake)?;
+        self._emit_emergency_withdraw_event(caller, stake);
+
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn start_reward(&mut self) -> Result<(), Error> {
+        if self.data::<Data>().reward_started {
+            return Err(Error::RewardStarted);
+        }
+
+        let data = self.data::<Data>();
+        data.reward_started = true;
+        data.last_update = T::env().block_timestamp();
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn stop_reward(&mut self) -> Result<(), Error> {
+        if !self.data::<Data>().reward_started {
+            return Err(Error::RewardNotStarted);
+        }
+
+        self._update_pool()?;
+        self.data::<Data>().reward_started = false;
+        Ok(())
+    }
+
+    #[modifiers(only_owner)]
+    default fn set_reward_rate(&mut self, reward_rate: Balance) -> Result<(), Error> {
+        // Rewards accrued so far are paid at the old rate
+        self._update_pool()?;
+        self.data::<Data>().reward_rate = reward_rate;
+        Ok(())
+    }
+
+    default fn get_reward_rate(&self) -> Balance {
+        self.data::<Data>().reward_rate
+    }
+
+    default fn is_reward_started(&self) -> bool {
+        self.data::<Data>().reward_started
+    }
+
+    default fn get_last_update(&self) -> Timestamp {
+        self.data::<Data>().last_update
+    }
+
+    default fn get_stake(&self, account: AccountId) -> Balance {
+        self.data::<Data>().stakes.get(&account).unwrap_or(0)
+    }
+
+    default fn get_total_staked(&self) -> Balance {
+        self.data::<Data>().total_staked
+    }
+
+    default fn pending_reward(&self, account: AccountId) -> Balance {
+        self._current_reward_per_share()
+            .and_then(|per_share| self._earned(&account, per_share))
+            .unwrap_or(0)
+    }
+
+    default fn get_staking_token(&self) -> AccountId {
+        self.data::<Data>().staking_token
+    }
+
+    default fn get_reward_token(&self) -> AccountId {
+        self.data::<Data>().reward_token
+    }
+}
+
+pub trait StakingInternal {
+    /// User must override this method in their contract.
+    fn _emit_staked_event(&self, _staker: AccountId, _amount: Balance);
+
+    /// User must override this method in their contract.
+    fn _emit_unstaked_event(&self, _staker: AccountId, _amount: Balance);
+
+    /// User must override this method in their contract.
+    fn _emit_reward_claimed_event(&self, _staker: AccountId, _amount: Balance);
+
+    /// User must override this method in their contract.
+    fn _emit_emergency_withdraw_event(&self, _staker: AccountId, _amount: Balance);
+
+    fn _init_staking(&mut self, staking_token: AccountId, reward_token: AccountId, reward_rate: Balance);
+
+    /// `acc_reward_per_share` including the rewards accrued since the last update
+    fn _current_reward_per_share(&self) -> Result<u128, Error>;
+
+    /// Pending rewards of `account` plus the ones earned with `per_share`
+    fn _earned(&self, account: &AccountId, per_share: u128) -> Result<Balance, Error>;
+
+    /// Accrue the rewards since the last update, has to be called before the total stake or the rate change
+    fn _update_pool(&mut self) -> Result<(), Error>;
+
+    /// Move the rewards earned by `account` to `pending`, has to be called before its stake changes
+    fn _update_staker(&mut self, account: &AccountId) -> Result<(), Error>;
+
+    /// Send the pending rewards of `account`, the stakes are never used to pay rewards.
+    /// On failure the rewards stay pending
+    fn _pay_reward(&mut self, account: AccountId) -> Result<Balance, Error>;
+
+    /// Reward tokens held by the pool, without the stakes when both tokens are the same
+    fn _reward_balance(&self) -> Balance;
+
+    /// Pull `amount` of the staking token from `from` and return the amount the pool actually received
+    fn _receive_staking_token(&mut self, from: AccountId, amount: Balance) -> Result<Balance, Error>;
+
+    fn _transfer_token(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), Error>;
+}
+
+fn overflow() -> Error {
+    Error::Custom(String::from("S::Overflow"))
+}
+
+impl<T> StakingInternal for T
+where
+    T: Storage<Data>,
+{
+    default fn _emit_staked_event(&self, _staker: AccountId, _amount: Balance) {}
+
+    default fn _emit_unstaked_event(&self, _staker: AccountId, _amount: Balance) {}
+
+    default fn _emit_reward_claimed_event(&self, _staker: AccountId, _amount: Balance) {}
+
+    default fn _emit_emergency_withdraw_event(&self, _staker: AccountId, _amount: Balance) {}
+
+    default fn _init_staking(&mut self, staking_token: AccountId, reward_token: AccountId, reward_rate: Balance) {
+        let data = self.data::<Data>();
+        data.staking_token = staking_token;
+        data.reward_token = reward_token;
+        data.reward_rate = reward_rate;
+    }
+
+    default fn _current_reward_per_share(&self) -> Result<u128, Error> {
+        let data = self.data::<Data>();
+        let now = T::env().block_timestamp();
+
+        if !data.reward_started || data.total_staked == 0 || now <= data.last_update {
+            return Ok(data.acc_reward_per_share);
+        }
+
+        let elapsed = (now - data.last_update) as u128;
+        let reward = mul_div(data.reward_rate, elapsed, MILLIS_PER_SECOND).ok_or_else(overflow)?;
+        let increase = mul_div(reward, MAGNITUDE, data.total_staked).ok_or_else(overflow)?;
+
+        data.acc_reward_per_share.checked_add(increase).ok_or_else(overflow)
+    }
+
+    default fn _earned(&self, account: &AccountId, per_share: u128) -> Result<Balance, Error> {
+        let data = self.data::<Data>();
+        let stake = data.stakes.get(account).unwrap_or(0);
+        let snapshot = data.snapshots.get(account).unwrap_or(0);
+        let earned = mul_div(stake, per_share - snapshot, MAGNITUDE).ok_or_else(overflow)?;
+
+        data.pending
+            .get(account)
+            .unwrap_or(0)
+            .checked_add(earned)
+            .ok_or_else(overflow)
+    }
+
+    default fn _update_pool(&mut self) -> Result<(), Error> {
+        let per_share = self._current_reward_per_share()?;
+
+        let data = self.data::<Data>();
+        data.acc_reward_per_share = per_share;
+        if data.reward_started {
+            data.last_update = T::env().block_timestamp();
+        }
+        Ok(())
+    }
+
+    default fn _update_staker(&mut self, account: &AccountId) -> Result<(), Error> {
+        let per_share = self.data::<Data>().acc_reward_per_share;
+        let earned = self._earned(account, per_share)?;
+
+        let data = self.data::<Data>();
+        data.pending.insert(account, &earned);
+        data.snapshots.insert(account, &per_share);
+        Ok(())
+    }
+
+    default fn _pay_reward(&mut self, account: AccountId) -> Result<Balance, Error> {
+        let data = self.data::<Data>();
+        let amount = data.pending.get(&account).unwrap_or(0);
+        if amount == 0 {
+            return Ok(0);
+        }
+
+        let reward_token = data.reward_token;
+        if self._reward_balance() < amount {
+            return Err(Error::NotEnoughBalance);
+        }
+
+        self.data::<Data>().pending.remove(&account);
+        if let Err(error) = self._transfer_token(reward_token, account, amount) {
+            self.data::<Data>().pending.insert(&account, &amount);
+            return Err(error);
+        }
+        self._emit_reward_claimed_event(account, amount);
+
+        Ok(amount)
+    }
+
+    default fn _reward_balance(&self) -> Balance {
+        let data = self.data::<Data>();
+        let balance = Psp22Ref::balance_of(&data.reward_token, T::env().account_id());
+
+        if data.reward_token == data.staking_token {
+            balance.saturating_sub(data.total_staked)
+        } else {
+            balance
+        }
+    }
+
+    default fn _receive_staking_token(&mut self, from: AccountId, amount: Balance) -> Result<Balance, Error> {
+        let staking_token = self.data::<Data>().staking_token;
+        let contract = T::env().account_id();
+
+        let balance_before = Psp22Ref::balance_of(&staking_token, contract);
+        let result =
+            Psp22Ref::transfer_from_builder(&staking_token, from, contract, amount, Vec::<u8>::new()).try_invoke();
+        if !matches!(result, Ok(Ok(Ok(_)))) {
+            return Err(Error::CannotTransfer);
+        }
+
+        Ok(Psp22Ref::balance_of(&staking_token, contract).saturating_sub(balance_before))
+    }
+
+    default fn _transfer_token(&self, token: AccountId, to: AccountId, amount: Balance) -> Result<(), Error> {
+        let result = Psp22Ref::transfer_builder(&token, to, amount, Vec::<u8>::new()).try_invoke();
+        if !matches!(result, Ok(Ok(Ok(_)))) {
+            return Err(Error::CannotTransfer);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ink::env::{test, DefaultEnvironment};
+
+    /// Pool without token transfers. It holds `rewards` reward tokens and the staking token
+    /// keeps `fee` of every stake, like a token with a transfer fee
+    #[derive(Default, Storage)]
+    struct Pool {
+        #[storage_field]
+        ownable: ownable::Data,
+        #[storage_field]
+        staking: Data,
+        rewards: Balance,
+        fee: Balance,
+    }
+
+    impl Staking for Pool {}
+
+    impl StakingInternal for Pool {
+        fn _reward_balance(&self) -> Balance {
+            self.rewards
+        }
+
+        fn _receive_staking_token(&mut self, _from: AccountId, amount: Balance) -> Result<Balance, Error> {
+            Ok(amount - self.fee)
+        }
+
+        fn _transfer_token(&self, _token: AccountId, _to: AccountId, _amount: Balance) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    /// Alice owns a pool paying 1_000 reward tokens per second
+    fn setup(rewards: Balance, fee: Balance) -> (Pool, test::DefaultAccounts<DefaultEnvironment>) {
+        let accounts = test::default_accounts::<DefaultEnvironment>();
+        test::set_caller::<DefaultEnvironment>(accounts.alice);
+        let mut pool = Pool {
+            rewards,
+            fee,
+            ..Default::default()
+        };
+        pool._init_with_owner(accounts.alice);
+        pool._init_staking(accounts.django, accounts.django, 1_000);
+
+        (pool, accounts)
+    }
+
+    #[ink::test]
+    fn emergency_withdraw_does_not_inflate_other_rewards() {
+        let (mut pool, accounts) = setup(Balance::MAX, 0);
+
+        for staker in [accounts.bob, accounts.charlie] {
+            test::set_caller::<DefaultEnvironment>(staker);
+            assert_eq!(pool.stake(1_000), Ok(()));
+        }
+        test::set_caller::<DefaultEnvironment>(accounts.alice);
+        assert_eq!(pool.start_reward(), Ok(()));
+
+        // Both earn 5_000 in the first 10 seconds, Bob leaves without them
+        test::set_block_timestamp::<DefaultEnvironment>(10_000);
+        test::set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(pool.emergency_withdraw(), Ok(()));
+        assert_eq!(pool.get_stake(accounts.bob), 0);
+        assert_eq!(pool.get_total_staked(), 1_000);
+        assert_eq!(pool.pending_reward(accounts.bob), 0);
+
+        // Charlie earns everything of the next 10 seconds
+        test::set_block_timestamp::<DefaultEnvironment>(20_000);
+        test::set_caller::<DefaultEnvironment>(accounts.charlie);
+        assert_eq!(pool.pending_reward(accounts.charlie), 15_000);
+        assert_eq!(pool.claim_reward(), Ok(15_000));
+    }
+
+    #[ink::test]
+    fn unstake_returns_the_stake_of_an_underfunded_pool() {
+        let (mut pool, accounts) = setup(0, 0);
+
+        test::set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(pool.stake(1_000), Ok(()));
+        test::set_caller::<DefaultEnvironment>(accounts.alice);
+        assert_eq!(pool.start_reward(), Ok(()));
+
+        test::set_block_timestamp::<DefaultEnvironment>(10_000);
+        test::set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(pool.unstake(1_000), Ok(()));
+        assert_eq!(pool.get_stake(accounts.bob), 0);
+        assert_eq!(pool.get_total_staked(), 0);
+        assert_eq!(pool.pending_reward(accounts.bob), 10_000);
+        assert_eq!(pool.claim_reward(), Err(Error::NotEnoughBalance));
+
+        // Once the pool is funded the rewards can be claimed
+        pool.rewards = 10_000;
+        assert_eq!(pool.claim_reward(), Ok(10_000));
+        assert_eq!(pool.pending_reward(accounts.bob), 0);
+    }
+
+    #[ink::test]
+    fn stake_books_the_amount_received() {
+        let (mut pool, accounts) = setup(Balance::MAX, 10);
+
+        test::set_caller::<DefaultEnvironment>(accounts.bob);
+        assert_eq!(pool.stake(1_000), Ok(()));
+        assert_eq!(pool.get_stake(accounts.bob), 990);
+        assert_eq!(pool.get_total_staked(), 990);
+
+        assert_eq!(pool.unstake(1_000), Err(Error::NotEnoughBalance));
+        assert_eq!(pool.unstake(990), Ok(()));
+        assert_eq!(pool.get_total_staked(), 0);
+    }
+}
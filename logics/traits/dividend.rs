@@ -0,0 +1,49 @@
+use openbrush::contracts::psp22::PSP22Error;
+use openbrush::traits::{AccountId, Balance};
+
+#[openbrush::wrapper]
+pub type DividendRef = dyn Dividend;
+
+#[openbrush::trait_definition]
+pub trait Dividend {
+    /// Share the transferred native currency between all holders in proportion to their balance. Only when the
+    /// reward token is native
+    #[ink(message, payable)]
+    fn distribute_native(&mut self) -> Result<(), PSP22Error>;
+
+    /// Pull `amount` of the reward token from the caller and share what is received between all holders.
+    /// The caller has to approve the token contract first
+    #[ink(message)]
+    fn distribute_psp22(&mut self, amount: Balance) -> Result<(), PSP22Error>;
+
+    /// Send all the dividends of the caller to the caller, returns the amount sent
+    #[ink(message)]
+    fn withdraw_dividend(&mut self) -> Result<Balance, PSP22Error>;
+
+    #[ink(message)]
+    fn withdrawable_dividend_of(&self, account: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn withdrawn_dividend_of(&self, account: AccountId) -> Balance;
+
+    #[ink(message)]
+    fn total_dividends_distributed(&self) -> Balance;
+
+    /// Pay dividends in `token`, or in native currency with `None`. Can not be changed after the first distribution
+    #[ink(message)]
+    fn set_reward_token(&mut self, token: Option<AccountId>) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn get_reward_token(&self) -> Option<AccountId>;
+
+    /// Stop `account` from earning dividends, usually done for pairs, the treasury and contracts
+    #[ink(message)]
+    fn exclude_from_dividends(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    /// Let `account` earn dividends again, starting from the next distribution
+    #[ink(message)]
+    fn include_in_dividends(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn is_excluded_from_dividends(&self, account: AccountId) -> bool;
+}
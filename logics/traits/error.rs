@@ -1,5 +1,7 @@
 use ink::prelude::string::String;
-use openbrush::{contracts::access_control::*, contracts::ownable::*};
+use openbrush::{
+    contracts::access_control::*, contracts::ownable::*, contracts::pausable::*, contracts::psp22::PSP22Error,
+};
 
 #[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
 #[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
@@ -49,6 +51,40 @@ pub enum Error {
     RewardStarted,
     RewardNotStarted,
     ClaimMustBeFalse,
+    AlreadyClaimed,
+    InvalidProof,
+    AirdropExpired,
+    AirdropNotExpired,
+    ClaimsStarted,
+    ScheduleExists,
+    ScheduleNotExist,
+    NotRevocable,
+    OwnableError(OwnableError),
+    AccessControlError(AccessControlError),
+    PausableError(PausableError),
+}
+
+/// Errors of the PSP22 fee token. PSP22 messages receive them as `PSP22Error::Custom` with a `F::` prefix
+#[derive(Debug, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo))]
+pub enum Psp22FeeError {
+    Custom(String),
+    MaxTxExceeded,
+    MaxWalletExceeded,
+    FeeTooHigh,
+    InvalidLimit,
+    InvalidFeeRecipients,
+    TradingDisabled,
+    TradingAlreadyEnabled,
+    CooldownActive,
+    InvalidCooldown,
+    Blacklisted,
+    RouterNotSet,
+    SwapFailed,
+    Overflow,
+    CapExceeded,
+    InvalidBatchLength,
+    PSP22Error(PSP22Error),
     OwnableError(OwnableError),
     AccessControlError(AccessControlError),
 }
@@ -79,3 +115,54 @@ impl From<AccessControlError> for Error {
         Error::AccessControlError(access)
     }
 }
+
+impl From<PausableError> for Error {
+    fn from(pausable: PausableError) -> Self {
+        Error::PausableError(pausable)
+    }
+}
+
+impl From<PSP22Error> for Psp22FeeError {
+    fn from(error: PSP22Error) -> Self {
+        Psp22FeeError::PSP22Error(error)
+    }
+}
+
+impl From<OwnableError> for Psp22FeeError {
+    fn from(ownable: OwnableError) -> Self {
+        Psp22FeeError::OwnableError(ownable)
+    }
+}
+
+impl From<AccessControlError> for Psp22FeeError {
+    fn from(access: AccessControlError) -> Self {
+        Psp22FeeError::AccessControlError(access)
+    }
+}
+
+impl From<Psp22FeeError> for PSP22Error {
+    fn from(error: Psp22FeeError) -> Self {
+        let message = match error {
+            Psp22FeeError::PSP22Error(error) => return error,
+            Psp22FeeError::OwnableError(ownable) => return ownable.into(),
+            Psp22FeeError::AccessControlError(access) => return access.into(),
+            Psp22FeeError::Custom(message) => message,
+            Psp22FeeError::MaxTxExceeded => String::from("F::MaxTxExceeded"),
+            Psp22FeeError::MaxWalletExceeded => String::from("F::MaxWalletExceeded"),
+            Psp22FeeError::FeeTooHigh => String::from("F::FeeTooHigh"),
+            Psp22FeeError::InvalidLimit => String::from("F::InvalidLimit"),
+            Psp22FeeError::InvalidFeeRecipients => String::from("F::InvalidFeeRecipients"),
+            Psp22FeeError::TradingDisabled => String::from("F::TradingDisabled"),
+            Psp22FeeError::TradingAlreadyEnabled => String::from("F::TradingAlreadyEnabled"),
+            Psp22FeeError::CooldownActive => String::from("F::CooldownActive"),
+            Psp22FeeError::InvalidCooldown => String::from("F::InvalidCooldown"),
+            Psp22FeeError::Blacklisted => String::from("F::Blacklisted"),
+            Psp22FeeError::RouterNotSet => String::from("F::RouterNotSet"),
+            Psp22FeeError::SwapFailed => String::from("F::SwapFailed"),
+            Psp22FeeError::Overflow => String::from("F::Overflow"),
+            Psp22FeeError::CapExceeded => String::from("F::CapExceeded"),
+            Psp22FeeError::InvalidBatchLength => String::from("F::InvalidBatchLength"),
+        };
+        PSP22Error::Custom(message.into())
+    }
+}
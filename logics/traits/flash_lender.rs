@@ -0,0 +1,51 @@
+use ink::prelude::vec::Vec;
+use openbrush::contracts::psp22::PSP22Error;
+use openbrush::traits::{AccountId, Balance};
+
+#[openbrush::wrapper]
+pub type FlashLenderRef = dyn FlashLender;
+
+#[openbrush::wrapper]
+pub type FlashBorrowerRef = dyn FlashBorrower;
+
+#[openbrush::trait_definition]
+pub trait FlashLender {
+    /// Maximum amount of `token` available for a flash loan, zero for unsupported tokens
+    #[ink(message)]
+    fn max_flash_loan(&self, token: AccountId) -> Balance;
+
+    /// Fee charged on top of a flash loan of `amount` of `token`
+    #[ink(message)]
+    fn flash_fee(&self, token: AccountId, amount: Balance) -> Result<Balance, PSP22Error>;
+
+    /// Mint `amount` of `token` to `receiver` and call its `FlashBorrower::on_flash_loan` with `data`.
+    /// Before returning from the callback the receiver has to approve this contract for the amount plus the fee,
+    /// which are burned afterwards
+    #[ink(message)]
+    fn flash_loan(
+        &mut self,
+        receiver: AccountId,
+        token: AccountId,
+        amount: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+
+    /// Set the flash loan fee in basis points
+    #[ink(message)]
+    fn set_flash_fee(&mut self, fee: u128) -> Result<(), PSP22Error>;
+}
+
+#[openbrush::trait_definition]
+pub trait FlashBorrower {
+    /// Called by the lender during `flash_loan` after `amount` of `token` was sent to this contract.
+    /// `initiator` is the account which requested the loan
+    #[ink(message)]
+    fn on_flash_loan(
+        &mut self,
+        initiator: AccountId,
+        token: AccountId,
+        amount: Balance,
+        fee: Balance,
+        data: Vec<u8>,
+    ) -> Result<(), PSP22Error>;
+}
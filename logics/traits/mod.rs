@@ -1,4 +1,13 @@
 pub mod admin;
+pub mod dividend;
 pub mod error;
+pub mod flash_lender;
+pub mod pause_manager;
 pub mod psp22_fee;
+pub mod psp22_permit;
+pub mod psp22_reflection;
+pub mod psp22_votes;
 pub mod psp34_traits;
+pub mod roles;
+pub mod router;
+pub mod staking;
@@ -0,0 +1,14 @@
+use crate::traits::error::Error;
+
+#[openbrush::wrapper]
+pub type PauseManagerRef = dyn PauseManager;
+
+#[openbrush::trait_definition]
+pub trait PauseManager {
+    /// This function halts transfers, mints, burns and attribute updates during an incident. Queries keep working. Only accounts with the PAUSER role can perform this function.
+    #[ink(message)]
+    fn pause(&mut self) -> Result<(), Error>;
+    /// This function resumes the contract after a pause. Only accounts with the PAUSER role can perform this function.
+    #[ink(message)]
+    fn unpause(&mut self) -> Result<(), Error>;
+}
@@ -1,26 +1,194 @@
-use openbrush::contracts::psp22::PSP22Error;
-use openbrush::traits::Balance;
+use crate::traits::error::Psp22FeeError;
+use ink::prelude::vec::Vec;
+use openbrush::traits::{AccountId, Balance, BlockNumber, Timestamp};
+
+/// Destination of a share of the collected transfer fee
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum FeeRecipient {
+    /// The share is transferred to the account
+    Account(AccountId),
+    /// The share is burnt and reduces the total supply
+    Burn,
+    /// The share is redistributed to all holders, requires the reflection mode
+    Reflect,
+    /// The share is accumulated on the token contract and added to liquidity through the router
+    Liquidity,
+}
+
+/// Policy of the `max_tx` and `max_wallet` limits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, scale::Encode, scale::Decode)]
+#[cfg_attr(feature = "std", derive(scale_info::TypeInfo, ink::storage::traits::StorageLayout))]
+pub enum Limit {
+    /// Share of the current total supply in basis points, follows mints and burns
+    Percentage(u128),
+    /// Fixed amount of tokens
+    Absolute(Balance),
+}
+
+impl Default for Limit {
+    fn default() -> Self {
+        Limit::Absolute(Balance::MAX)
+    }
+}
 
 #[openbrush::wrapper]
 pub type Psp22FeeRef = dyn Psp22Fee;
 
 #[openbrush::trait_definition]
 pub trait Psp22Fee {
+    /// Set the wallet limit in basis points of the current total supply (100 = 1%)
+    #[ink(message)]
+    fn set_max_wallet(&mut self, max_wallet: u128) -> Result<(), Psp22FeeError>;
+
+    /// Set the wallet limit to a fixed amount of tokens
+    #[ink(message)]
+    fn set_max_wallet_amount(&mut self, max_wallet: Balance) -> Result<(), Psp22FeeError>;
+
+    /// Set the transaction limit in basis points of the current total supply (100 = 1%)
     #[ink(message)]
-    fn set_max_wallet(&mut self, max_wallet: u128) -> Result<(), PSP22Error>;
+    fn set_max_tx(&mut self, max_tx: u128) -> Result<(), Psp22FeeError>;
 
+    /// Set the transaction limit to a fixed amount of tokens
     #[ink(message)]
-    fn set_max_tx(&mut self, max_tx: u128) -> Result<(), PSP22Error>;
+    fn set_max_tx_amount(&mut self, max_tx: Balance) -> Result<(), Psp22FeeError>;
 
+    /// Effective wallet limit for the current total supply
     #[ink(message)]
     fn get_max_wallet(&mut self) -> Balance;
 
+    /// Effective transaction limit for the current total supply
     #[ink(message)]
     fn get_max_tx(&mut self) -> Balance;
 
     #[ink(message)]
-    fn set_fee(&mut self, fee: u128) -> Result<(), PSP22Error>;
+    fn get_max_wallet_policy(&self) -> Limit;
 
+    #[ink(message)]
+    fn get_max_tx_policy(&self) -> Limit;
+
+    /// Set the transfer fee in basis points (100 = 1%). Fails if it is above `MAX_FEE`
+    #[ink(message)]
+    fn set_fee(&mut self, fee: u128) -> Result<(), Psp22FeeError>;
+
+    /// Transfer fee in basis points
     #[ink(message)]
     fn get_fee(&mut self) -> u128;
+
+    /// Set the fee in basis points charged when tokens are bought from a registered pair
+    #[ink(message)]
+    fn set_buy_fee(&mut self, fee: u128) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn get_buy_fee(&self) -> u128;
+
+    /// Set the fee in basis points charged when tokens are sold to a registered pair
+    #[ink(message)]
+    fn set_sell_fee(&mut self, fee: u128) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn get_sell_fee(&self) -> u128;
+
+    /// Register a market pair, transfers from it are taxed as buys and transfers to it as sells
+    #[ink(message)]
+    fn add_pair(&mut self, pair: AccountId) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn remove_pair(&mut self, pair: AccountId) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn is_pair(&self, account: AccountId) -> bool;
+
+    /// Exempt `account` from the transfer fee, both as sender and as receiver
+    #[ink(message)]
+    fn add_fee_exempt(&mut self, account: AccountId) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn remove_fee_exempt(&mut self, account: AccountId) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn is_fee_exempt(&self, account: AccountId) -> bool;
+
+    /// Exempt `account` from the `max_tx` and `max_wallet` limits
+    #[ink(message)]
+    fn add_limit_exempt(&mut self, account: AccountId) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn remove_limit_exempt(&mut self, account: AccountId) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn is_limit_exempt(&self, account: AccountId) -> bool;
+
+    /// Set where the collected fee goes. Each destination gets a weight in basis points
+    /// and the weights must sum up to 10000 (100%). The zero address is not a valid destination, use `Burn`
+    #[ink(message)]
+    fn set_fee_recipients(&mut self, recipients: Vec<(FeeRecipient, u128)>) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn get_fee_recipients(&self) -> Vec<(FeeRecipient, u128)>;
+
+    /// Set the router used to add the accumulated liquidity fee to the pool
+    #[ink(message)]
+    fn set_router(&mut self, router: AccountId) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn get_router(&self) -> Option<AccountId>;
+
+    /// Set the amount of accumulated liquidity fee which triggers swap and liquify on the next transfer.
+    /// Zero disables the automatic trigger
+    #[ink(message)]
+    fn set_swap_threshold(&mut self, threshold: Balance) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn get_swap_threshold(&self) -> Balance;
+
+    /// Amount of liquidity fee held by the token contract and waiting to be added to the pool
+    #[ink(message)]
+    fn get_accumulated_liquidity(&self) -> Balance;
+
+    /// Add the whole accumulated liquidity fee to the pool right away
+    #[ink(message)]
+    fn swap_and_liquify(&mut self) -> Result<(), Psp22FeeError>;
+
+    /// Open trading for everyone. Can be called only once, the launch window starts at the current block
+    #[ink(message)]
+    fn enable_trading(&mut self) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn is_trading_enabled(&self) -> bool;
+
+    /// Configure the anti-bot protection applied during the first `blocks` blocks after trading is enabled:
+    /// every taxed transfer pays `fee` basis points and `max_tx` (basis points of the supply) is tightened.
+    /// Can only be changed before trading is enabled
+    #[ink(message)]
+    fn set_launch_protection(&mut self, blocks: BlockNumber, fee: u128, max_tx: u128) -> Result<(), Psp22FeeError>;
+
+    /// Returns the launch window length in blocks, its fee and its transaction limit
+    #[ink(message)]
+    fn get_launch_protection(&self) -> (BlockNumber, u128, Limit);
+
+    #[ink(message)]
+    fn is_launch_window(&self) -> bool;
+
+    /// Set the minimum time in milliseconds between two transfers sent (or bought from a pair)
+    /// by the same non-exempt account. Zero disables the cooldown
+    #[ink(message)]
+    fn set_cooldown(&mut self, cooldown: Timestamp) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn get_cooldown(&self) -> Timestamp;
+
+    /// Timestamp from which `account` may transfer again, zero if it is not limited
+    #[ink(message)]
+    fn next_allowed_transfer(&self, account: AccountId) -> Timestamp;
+
+    /// Freeze `accounts`: they can neither send, receive nor be approved as spenders
+    #[ink(message)]
+    fn add_to_blacklist(&mut self, accounts: Vec<AccountId>) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn remove_from_blacklist(&mut self, accounts: Vec<AccountId>) -> Result<(), Psp22FeeError>;
+
+    #[ink(message)]
+    fn is_blacklisted(&self, account: AccountId) -> bool;
 }
@@ -0,0 +1,33 @@
+use openbrush::contracts::psp22::PSP22Error;
+use openbrush::traits::{AccountId, Balance, Timestamp};
+
+#[openbrush::wrapper]
+pub type Psp22PermitRef = dyn Psp22Permit;
+
+#[openbrush::trait_definition]
+pub trait Psp22Permit {
+    /// Approve `spender` for `value` tokens of `owner` with a signature of the owner instead of an `approve` call.
+    /// The ECDSA signature covers `permit_hash(owner, spender, value, deadline)`, which includes the current nonce
+    /// of the owner and the address of this contract. Fails once `deadline` is passed or the nonce is used
+    #[ink(message)]
+    fn permit(
+        &mut self,
+        owner: AccountId,
+        spender: AccountId,
+        value: Balance,
+        deadline: Timestamp,
+        signature: [u8; 65],
+    ) -> Result<(), PSP22Error>;
+
+    /// Next nonce to sign for `owner`, it is increased by every successful permit
+    #[ink(message)]
+    fn nonces(&self, owner: AccountId) -> u64;
+
+    /// Hash binding permits to this contract, a signature can not be replayed on another token
+    #[ink(message)]
+    fn domain_separator(&self) -> [u8; 32];
+
+    /// Hash the owner has to sign to permit `spender` with the current nonce
+    #[ink(message)]
+    fn permit_hash(&self, owner: AccountId, spender: AccountId, value: Balance, deadline: Timestamp) -> [u8; 32];
+}
@@ -0,0 +1,26 @@
+use openbrush::contracts::psp22::PSP22Error;
+use openbrush::traits::{AccountId, Balance};
+
+#[openbrush::wrapper]
+pub type Psp22ReflectionRef = dyn Psp22Reflection;
+
+#[openbrush::trait_definition]
+pub trait Psp22Reflection {
+    /// Returns true if the token was deployed with holder redistribution enabled
+    #[ink(message)]
+    fn is_reflection_enabled(&self) -> bool;
+
+    /// Total amount of fees redistributed to holders so far
+    #[ink(message)]
+    fn total_reflected_fees(&self) -> Balance;
+
+    /// Stop `account` from receiving its share of reflected fees, usually done for pairs and contracts
+    #[ink(message)]
+    fn exclude_from_reflection(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn include_in_reflection(&mut self, account: AccountId) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn is_excluded_from_reflection(&self, account: AccountId) -> bool;
+}
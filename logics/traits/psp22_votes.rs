@@ -0,0 +1,35 @@
+use openbrush::contracts::psp22::PSP22Error;
+use openbrush::traits::{AccountId, Balance, BlockNumber};
+
+#[openbrush::wrapper]
+pub type Psp22VotesRef = dyn Psp22Votes;
+
+#[openbrush::trait_definition]
+pub trait Psp22Votes {
+    /// Current voting power of `account`, i.e. the balances delegated to it
+    #[ink(message)]
+    fn get_votes(&self, account: AccountId) -> Balance;
+
+    /// Voting power of `account` at the end of `block`. The block must be already mined
+    #[ink(message)]
+    fn get_past_votes(&self, account: AccountId, block: BlockNumber) -> Result<Balance, PSP22Error>;
+
+    /// Total supply at the end of `block`. The block must be already mined
+    #[ink(message)]
+    fn get_past_total_supply(&self, block: BlockNumber) -> Result<Balance, PSP22Error>;
+
+    /// Account which receives the voting power of `account`. Accounts delegate to themselves by default
+    #[ink(message)]
+    fn delegates(&self, account: AccountId) -> AccountId;
+
+    /// Move the voting power of the caller to `delegatee`
+    #[ink(message)]
+    fn delegate(&mut self, delegatee: AccountId) -> Result<(), PSP22Error>;
+
+    #[ink(message)]
+    fn num_checkpoints(&self, account: AccountId) -> u32;
+
+    /// Block number and voting power of the checkpoint at `index` of `account`
+    #[ink(message)]
+    fn checkpoints(&self, account: AccountId, index: u32) -> Option<(BlockNumber, Balance)>;
+}
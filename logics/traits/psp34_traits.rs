@@ -10,10 +10,10 @@ pub type Psp34Ref = dyn Psp34Traits + PSP34 + PSP34Metadata;
 
 #[openbrush::trait_definition]
 pub trait Psp34Traits {
-    /// This function sets the baseURI for the NFT contract. Only Contract Owner can perform this function. baseURI is the location of the metadata files if the NFT collection use external source to keep their NFT artwork. ArtZero uses IPFS by default, the baseURI can have format like this: ipfs://<hash_ID>/
+    /// This function sets the baseURI for the NFT contract. Only accounts with the METADATA_ADMIN role can perform this function. baseURI is the location of the metadata files if the NFT collection use external source to keep their NFT artwork. ArtZero uses IPFS by default, the baseURI can have format like this: ipfs://<hash_ID>/
     #[ink(message)]
     fn set_base_uri(&mut self, uri: String) -> Result<(), Error>;
-    /// This function set the attributes to each NFT. Only Contract Owner can perform this function. The metadata input is an array of [(attribute, value)]. The attributes in ArtZero platform are the NFT traits.
+    /// This function set the attributes to each NFT. Only accounts with the METADATA_ADMIN role can perform this function. The metadata input is an array of [(attribute, value)]. The attributes in ArtZero platform are the NFT traits.
     #[ink(message)]
     fn set_multiple_attributes(
         &mut self,
@@ -0,0 +1,12 @@
+use openbrush::contracts::access_control::RoleType;
+
+/// Manages fees, limits, exemptions and trading settings of the fee token
+pub const FEE_MANAGER: RoleType = ink::selector_id!("FEE_MANAGER");
+/// Mints tokens and manages mint settings
+pub const MINTER: RoleType = ink::selector_id!("MINTER");
+/// Updates base URI and token attributes of collections
+pub const METADATA_ADMIN: RoleType = ink::selector_id!("METADATA_ADMIN");
+/// Withdraws native and PSP22 funds held by the contracts
+pub const TREASURER: RoleType = ink::selector_id!("TREASURER");
+/// Pauses and unpauses transfers, mints and attribute updates during an incident
+pub const PAUSER: RoleType = ink::selector_id!("PAUSER");
@@ -0,0 +1,19 @@
+use openbrush::contracts::psp22::PSP22Error;
+use openbrush::traits::{AccountId, Balance};
+
+#[openbrush::wrapper]
+pub type LiquidityRouterRef = dyn LiquidityRouter;
+
+#[openbrush::trait_definition]
+pub trait LiquidityRouter {
+    /// Pulls `amount` of `token` from the caller with `transfer_from`, swaps half of it against the
+    /// other asset of the pair and adds both halves as liquidity. LP tokens are sent to `lp_receiver`.
+    /// Returns the amount of liquidity minted
+    #[ink(message)]
+    fn swap_and_add_liquidity(
+        &mut self,
+        token: AccountId,
+        amount: Balance,
+        lp_receiver: AccountId,
+    ) -> Result<Balance, PSP22Error>;
+}
@@ -0,0 +1,49 @@
+use crate::traits::error::Error;
+use openbrush::traits::{AccountId, Balance, Timestamp};
+
+#[openbrush::wrapper]
+pub type StakingRef = dyn Staking;
+
+#[openbrush::trait_definition]
+pub trait Staking {
+    /// This function pulls `amount` of the staking token from the caller, who has to approve the pool first. Only the amount received by the pool is staked, so fee tokens are accounted correctly.
+    #[ink(message)]
+    fn stake(&mut self, amount: Balance) -> Result<(), Error>;
+    /// This function sends `amount` of the staked tokens back to the caller together with the pending rewards.
+    /// Rewards the pool can not pay yet stay pending, the stake is returned anyway.
+    #[ink(message)]
+    fn unstake(&mut self, amount: Balance) -> Result<(), Error>;
+    /// This function sends the pending rewards to the caller and returns the amount sent
+    #[ink(message)]
+    fn claim_reward(&mut self) -> Result<Balance, Error>;
+    /// This function sends back the whole stake of the caller without any reward. It works even when rewards can not be paid.
+    #[ink(message)]
+    fn emergency_withdraw(&mut self) -> Result<(), Error>;
+    /// This function starts a reward period, the rewards accrue from now on. Only Contract Owner can perform this function.
+    #[ink(message)]
+    fn start_reward(&mut self) -> Result<(), Error>;
+    /// This function stops the current reward period. Only Contract Owner can perform this function.
+    #[ink(message)]
+    fn stop_reward(&mut self) -> Result<(), Error>;
+    /// This function sets the amount of reward tokens paid to all stakers per second. Only Contract Owner can perform this function.
+    #[ink(message)]
+    fn set_reward_rate(&mut self, reward_rate: Balance) -> Result<(), Error>;
+    #[ink(message)]
+    fn get_reward_rate(&self) -> Balance;
+    #[ink(message)]
+    fn is_reward_started(&self) -> bool;
+    /// This function returns the timestamp of the last reward update
+    #[ink(message)]
+    fn get_last_update(&self) -> Timestamp;
+    #[ink(message)]
+    fn get_stake(&self, account: AccountId) -> Balance;
+    #[ink(message)]
+    fn get_total_staked(&self) -> Balance;
+    /// This function returns the rewards `account` can claim now
+    #[ink(message)]
+    fn pending_reward(&self, account: AccountId) -> Balance;
+    #[ink(message)]
+    fn get_staking_token(&self) -> AccountId;
+    #[ink(message)]
+    fn get_reward_token(&self) -> AccountId;
+}